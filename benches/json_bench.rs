@@ -6,7 +6,7 @@ extern crate test;
 use std::fmt::Write;
 use test::Bencher;
 
-use jsondata::Json;
+use jsondata::{Framing, Json, Jsons, ParLines};
 
 #[bench]
 fn bench_null(b: &mut Bencher) {
@@ -126,3 +126,30 @@ fn bench_json5_num(b: &mut Bencher) {
     let inp = r#" -Infinity"#;
     b.iter(|| inp.parse::<Json>().unwrap().compute().unwrap());
 }
+
+fn ndjson_text(n: usize) -> Vec<u8> {
+    let mut text = String::new();
+    for i in 0..n {
+        writeln!(
+            text,
+            r#"{{"id": {}, "name": "item-{}", "tags": ["a", "b"], "price": {}.25}}"#,
+            i, i, i
+        )
+        .unwrap();
+    }
+    text.into_bytes()
+}
+
+#[bench]
+fn bench_jsons_ndjson(b: &mut Bencher) {
+    let text = ndjson_text(10_000);
+    b.bytes = text.len() as u64;
+    b.iter(|| Jsons::from(text.as_slice()).framing(Framing::Ndjson).count());
+}
+
+#[bench]
+fn bench_par_lines(b: &mut Bencher) {
+    let text = ndjson_text(10_000);
+    b.bytes = text.len() as u64;
+    b.iter(|| ParLines::from(text.as_slice()).chunk_size(64 * 1024).count());
+}
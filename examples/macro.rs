@@ -91,7 +91,7 @@ fn main() {
         f_ref.field1 as f64,
     );
 
-    let jval: Json = Json::try_from(f_ref.clone()).unwrap();
+    let jval: Json = Json::from(f_ref.clone());
     let f: Floats = Floats::try_from(jval.clone()).unwrap();
 
     assert_eq!(jval.to_string(), ref_s);
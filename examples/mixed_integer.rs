@@ -79,7 +79,7 @@ fn main() {
     for (i, text) in texts.iter().enumerate() {
         let out = F64::try_from(Json::from_str(text).unwrap());
         match i {
-            0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 => println!("{:?}", out.unwrap()),
+            0..=9 => println!("{:?}", out.unwrap()),
             _ => assert!(out.is_err()),
         }
     }
@@ -0,0 +1,128 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+// Serialize Json values as per JSON Canonicalization Scheme, RFC-8785.
+
+use std::fmt::Write;
+
+use crate::{json::Json, property::Property, Error, Result};
+
+pub(crate) fn write<W: Write>(w: &mut W, json: &Json) -> Result<()> {
+    let json = json.resolve();
+    match json {
+        Json::Null => write_str(w, "null"),
+        Json::Bool(true) => write_str(w, "true"),
+        Json::Bool(false) => write_str(w, "false"),
+        // JCS numbers are IEEE-754 double precision values.
+        Json::Integer(item) => write_number(w, item.integer_result()? as f64),
+        Json::Float(item) => write_number(w, item.float_result()?),
+        Json::String(s) => write_string(w, s),
+        Json::Array(items) => {
+            write_str(w, "[")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write_str(w, ",")?;
+                }
+                write(w, item)?;
+            }
+            write_str(w, "]")
+        }
+        Json::Object(props) => {
+            // properties are sorted by their key's UTF-16 code units.
+            let mut props: Vec<&Property> = props.iter().collect();
+            props
+                .sort_by(|a, b| a.as_key().encode_utf16().cmp(b.as_key().encode_utf16()));
+
+            write_str(w, "{")?;
+            for (i, prop) in props.into_iter().enumerate() {
+                if i > 0 {
+                    write_str(w, ",")?;
+                }
+                write_string(w, prop.as_key())?;
+                write_str(w, ":")?;
+                write(w, prop.as_value())?;
+            }
+            write_str(w, "}")
+        }
+        Json::__Error(err) => Err(err.clone()),
+        _ => err_at!(InvalidType, msg: "{}", json.type_name()),
+    }
+}
+
+fn write_str<W: Write>(w: &mut W, s: &str) -> Result<()> {
+    err_at!(IoError, w.write_str(s))
+}
+
+// Strings are serialized with minimal escaping, only quotation mark,
+// reverse solidus and control characters are escaped.
+fn write_string<W: Write>(w: &mut W, val: &str) -> Result<()> {
+    write_str(w, "\"")?;
+
+    let mut start = 0;
+    for (i, byte) in val.bytes().enumerate() {
+        let esc = match byte {
+            b'"' => "\\\"",
+            b'\\' => "\\\\",
+            b'\x08' => "\\b",
+            b'\x0c' => "\\f",
+            b'\n' => "\\n",
+            b'\r' => "\\r",
+            b'\t' => "\\t",
+            0..=0x1f => "",
+            _ => continue,
+        };
+        write_str(w, &val[start..i])?;
+        if esc.is_empty() {
+            err_at!(IoError, write!(w, "\\u{:04x}", byte))?;
+        } else {
+            write_str(w, esc)?;
+        }
+        start = i + 1;
+    }
+    write_str(w, &val[start..])?;
+
+    write_str(w, "\"")
+}
+
+// Numbers are serialized as per ECMAScript's Number.prototype.toString(),
+// refer section 3.2.2.3 of RFC-8785.
+fn write_number<W: Write>(w: &mut W, val: f64) -> Result<()> {
+    if !val.is_finite() {
+        return err_at!(InvalidNumber, msg: "{} is not allowed", val);
+    } else if val == 0.0 {
+        return write_str(w, "0");
+    } else if val < 0.0 {
+        write_str(w, "-")?;
+    }
+
+    // shortest round-trip digits, along with the decimal exponent. When
+    // two such digit sequences are equally close to the value, pick the
+    // even one, by re-formatting with exact precision.
+    let text = format!("{:e}", val.abs());
+    let prec = text.split_once('e').unwrap().0.len().saturating_sub(2);
+    let text = format!("{:.*e}", prec, val.abs());
+    let (mantissa, exp) = text.split_once('e').unwrap();
+    let digits: String = mantissa.chars().filter(|ch| *ch != '.').collect();
+    let (k, n) = (digits.len() as i32, exp.parse::<i32>().unwrap() + 1);
+
+    let res = if k <= n && n <= 21 {
+        write!(w, "{}{}", digits, "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (a, b) = digits.split_at(n as usize);
+        write!(w, "{}.{}", a, b)
+    } else if -6 < n && n <= 0 {
+        write!(w, "0.{}{}", "0".repeat(-n as usize), digits)
+    } else {
+        let sign = if n > 0 { '+' } else { '-' };
+        let (a, b) = digits.split_at(1);
+        if b.is_empty() {
+            write!(w, "{}e{}{}", a, sign, (n - 1).abs())
+        } else {
+            write!(w, "{}.{}e{}{}", a, b, sign, (n - 1).abs())
+        }
+    };
+    err_at!(IoError, res)
+}
+
+#[cfg(test)]
+#[path = "canonical_test.rs"]
+mod canonical_test;
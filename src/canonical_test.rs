@@ -0,0 +1,113 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use crate::{json::Json, Error};
+
+#[test]
+fn test_canonical_rfc_example() {
+    let text = r#"{
+      "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+      "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+      "literals": [null, true, false]
+    }"#;
+    let json: Json = text.parse().unwrap();
+
+    let refs = concat!(
+        r#"{"literals":[null,true,false],"#,
+        r#""numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"#,
+        r#""string":"€$\u000f\nA'B\"\\\\\"/"}"#,
+    );
+    assert_eq!(json.to_canonical_string().unwrap(), refs);
+}
+
+#[test]
+fn test_canonical_sort() {
+    let text = r#"{
+      "\u20ac": "Euro Sign",
+      "\r": "Carriage Return",
+      "\ufb33": "Hebrew Letter Dalet With Dagesh",
+      "1": "One",
+      "\ud83d\ude00": "Emoji: Grinning Face",
+      "\u0080": "Control",
+      "\u00f6": "Latin Small Letter O With Diaeresis"
+    }"#;
+    let json: Json = text.parse().unwrap();
+
+    let refs = concat!(
+        "{\"\\r\":\"Carriage Return\",",
+        "\"1\":\"One\",",
+        "\"\u{80}\":\"Control\",",
+        "\"ö\":\"Latin Small Letter O With Diaeresis\",",
+        "\"€\":\"Euro Sign\",",
+        "\"😀\":\"Emoji: Grinning Face\",",
+        "\"\u{fb33}\":\"Hebrew Letter Dalet With Dagesh\"}",
+    );
+    assert_eq!(json.to_canonical_string().unwrap(), refs);
+}
+
+#[test]
+fn test_canonical_numbers() {
+    let testcases: Vec<(u64, &str)> = vec![
+        (0x0000000000000000, "0"),
+        (0x8000000000000000, "0"),
+        (0x0000000000000001, "5e-324"),
+        (0x8000000000000001, "-5e-324"),
+        (0x7fefffffffffffff, "1.7976931348623157e+308"),
+        (0xffefffffffffffff, "-1.7976931348623157e+308"),
+        (0x4340000000000000, "9007199254740992"),
+        (0xc340000000000000, "-9007199254740992"),
+        (0x4430000000000000, "295147905179352830000"),
+        (0x44b52d02c7e14af5, "9.999999999999997e+22"),
+        (0x44b52d02c7e14af6, "1e+23"),
+        (0x44b52d02c7e14af7, "1.0000000000000001e+23"),
+        (0x444b1ae4d6e2ef4e, "999999999999999700000"),
+        (0x444b1ae4d6e2ef4f, "999999999999999900000"),
+        (0x444b1ae4d6e2ef50, "1e+21"),
+        (0x3eb0c6f7a0b5ed8c, "9.999999999999997e-7"),
+        (0x3eb0c6f7a0b5ed8d, "0.000001"),
+        (0x41b3de4355555553, "333333333.3333332"),
+        (0x41b3de4355555554, "333333333.33333325"),
+        (0x41b3de4355555555, "333333333.3333333"),
+        (0x41b3de4355555556, "333333333.3333334"),
+        (0x41b3de4355555557, "333333333.33333343"),
+        (0xbecbf647612f3696, "-0.0000033333333333333333"),
+        (0x43143ff3c1cb0959, "1424953923781206.2"),
+    ];
+
+    for (bits, refs) in testcases.into_iter() {
+        let json = Json::new(f64::from_bits(bits));
+        assert_eq!(json.to_canonical_string().unwrap(), refs, "{:x}", bits);
+    }
+
+    let json: Json = "[10, -0x10, 1000000000000000000000000]".parse().unwrap();
+    assert_eq!(json.to_canonical_string().unwrap(), "[10,-16,1e+24]");
+}
+
+#[test]
+fn test_canonical_fail() {
+    let json: Json = "[1, NaN]".parse().unwrap();
+    match json.to_canonical_string() {
+        Err(Error::InvalidNumber(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let json: Json = r#"{"a": Infinity}"#.parse().unwrap();
+    match json.to_canonical_string() {
+        Err(Error::InvalidNumber(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let json = Json::new(vec![Json::new(1)]).range(5..);
+    match json.to_canonical_string() {
+        Err(Error::IndexOutofBound(_, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn test_canonical_equal() {
+    let a: Json =
+        r#"{"b": [1.0, 0x10, "\u0041"], "a": {"y": 1e2, "x": -0.0}}"#.parse().unwrap();
+    let b: Json =
+        r#"{ "a" : { "x" : 0, "y" : 100 }, "b" : [ 1, 16, "A" ] }"#.parse().unwrap();
+    assert_eq!(a.to_canonical_string().unwrap(), b.to_canonical_string().unwrap());
+}
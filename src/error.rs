@@ -13,6 +13,11 @@ use std::{error, fmt, result};
 ///
 /// ```ignore
 /// use crate::Error;
+/// err_at!(ParseFail, at: lex.to_location(), msg: "bad token");
+/// ```
+///
+/// ```ignore
+/// use crate::Error;
 /// err_at!(ParseError, std::io::read(buf));
 /// ```
 ///
@@ -23,6 +28,10 @@ use std::{error, fmt, result};
 ///
 #[macro_export]
 macro_rules! err_at {
+    ($v:ident, at: $loc:expr, msg: $($arg:expr),+) => {{
+        let prefix = format!("{}:{}", file!(), line!());
+        Err(Error::$v(prefix, format!($($arg),+), $loc))
+    }};
     ($v:ident, msg: $($arg:expr),+) => {{
         let prefix = format!("{}:{}", file!(), line!());
         Err(Error::$v(prefix, format!($($arg),+)))
@@ -54,8 +63,11 @@ macro_rules! err_at {
 /// specific error handling.
 #[derive(Clone, PartialEq)]
 pub enum Error {
-    /// Failed to parse JSON text.
-    ParseFail(String, String),
+    /// Failed to parse JSON text, at [Location].
+    ParseFail(String, String, Location),
+    /// JSON text exceeds one of the resource limits configured in
+    /// [crate::Parser], at [Location].
+    LimitExceeded(String, String, Location),
     /// Failed to add two Json values.
     AddFail(String, String),
     /// Failed to subract two Json values.
@@ -92,6 +104,8 @@ pub enum Error {
     InvalidNumber(String, String),
     /// Failed processing json-pointer.
     JptrFail(String, String),
+    /// Failed compiling JSONPath query.
+    JpathFail(String, String),
     /// std::io::Error returned by string processing API, while iterating
     /// on [`crate::Jsons`] stream of text.
     IoError(String, String),
@@ -100,7 +114,8 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         match self {
-            Error::ParseFail(p, m) => write!(f, "{} ParseFail:{}", p, m),
+            Error::ParseFail(p, m, l) => write!(f, "{} ParseFail:{} {}", p, m, l),
+            Error::LimitExceeded(p, m, l) => write!(f, "{} LimitExceeded:{} {}", p, m, l),
             Error::AddFail(p, m) => write!(f, "{} AddFail:{}", p, m),
             Error::SubFail(p, m) => write!(f, "{} SubFail:{}", p, m),
             Error::MulFail(p, m) => write!(f, "{} MulFail:{}", p, m),
@@ -118,11 +133,45 @@ impl fmt::Display for Error {
             Error::AppendString(p, m) => write!(f, "{} AppendString:{}", p, m),
             Error::InvalidNumber(p, m) => write!(f, "{} InvalidNumber:{}", p, m),
             Error::JptrFail(p, m) => write!(f, "{} JptrFail:{}", p, m),
+            Error::JpathFail(p, m) => write!(f, "{} JpathFail:{}", p, m),
             Error::IoError(p, m) => write!(f, "{} IoError:{}", p, m),
         }
     }
 }
 
+impl Error {
+    /// Return the location within JSON text where parsing failed. Returns
+    /// None for errors that are not related to parsing.
+    ///
+    /// ```
+    /// use jsondata::Parser;
+    ///
+    /// let err = Parser::new().parse("[1,\n nil]").unwrap_err();
+    /// let loc = err.location().unwrap();
+    /// assert_eq!((loc.offset(), loc.line(), loc.column()), (5, 2, 2));
+    /// assert_eq!(loc.expected(), Some("null"));
+    /// assert_eq!(loc.found(), Some("nil"));
+    /// ```
+    pub fn location(&self) -> Option<&Location> {
+        match self {
+            Error::ParseFail(_, _, loc) => Some(loc),
+            Error::LimitExceeded(_, _, loc) => Some(loc),
+            _ => None,
+        }
+    }
+
+    // Locate error within the document, for text captured at ``base``.
+    pub(crate) fn relocate(self, base: &Location) -> Error {
+        match self {
+            Error::ParseFail(p, m, loc) => Error::ParseFail(p, m, loc.relocate(base)),
+            Error::LimitExceeded(p, m, loc) => {
+                Error::LimitExceeded(p, m, loc.relocate(base))
+            }
+            err => err,
+        }
+    }
+}
+
 impl fmt::Debug for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
         write!(f, "{}", self)
@@ -130,3 +179,68 @@ impl fmt::Debug for Error {
 }
 
 impl error::Error for Error {}
+
+/// Location within JSON text where parsing failed, refer [Error::location].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Location {
+    offset: usize,
+    line: usize,
+    column: usize,
+    expected: Option<String>,
+    found: Option<String>,
+}
+
+impl Location {
+    pub(crate) fn new(offset: usize, line: usize, column: usize) -> Location {
+        Location { offset, line, column, expected: None, found: None }
+    }
+
+    pub(crate) fn expect(mut self, expected: &str, found: &str) -> Location {
+        self.expected = Some(expected.to_string());
+        self.found = Some(found.to_string());
+        self
+    }
+
+    pub(crate) fn relocate(mut self, base: &Location) -> Location {
+        if self.line == 1 {
+            self.column += base.column - 1;
+        }
+        self.offset += base.offset;
+        self.line += base.line - 1;
+        self
+    }
+
+    /// Byte offset into the JSON text.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Line number, starting from 1.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Column number within the line, starting from 1. Columns are
+    /// counted in bytes, hence a multi-byte UTF-8 character advances the
+    /// column by its encoded length.
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Token the parser expected at this location, if known.
+    pub fn expected(&self) -> Option<&str> {
+        self.expected.as_deref()
+    }
+
+    /// Text found at this location, in place of the expected token.
+    /// Empty string implies end of text.
+    pub fn found(&self) -> Option<&str> {
+        self.found.as_deref()
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> result::Result<(), fmt::Error> {
+        write!(f, "at offset:{} line:{} col:{}", self.offset, self.line, self.column)
+    }
+}
@@ -0,0 +1,270 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use crate::parse::{Parser, Profile};
+use crate::{json::Json, lex::Lex, Error, Result};
+
+/// Feeder is a push parser for a stream of JSON text, supplied as byte
+/// chunks of any size, like those received from a socket. Unlike
+/// [crate::Jsons], that pulls from a blocking [std::io::Read], Feeder
+/// does not block, hence it is suitable for event loops.
+///
+/// Chunks can split the text anywhere, including in the middle of an
+/// escape sequence or of a UTF-8 encoded character. Bytes are buffered
+/// until a JSON value is complete, which is then parsed and returned by
+/// [Feeder::feed]. Values that are not self delimiting, like numbers,
+/// are complete only after a following whitespace, or when the stream
+/// is closed using [Feeder::finish].
+///
+/// ```
+/// use jsondata::{Feeder, Json};
+///
+/// let mut feeder = Feeder::new();
+/// assert!(feeder.feed(b"[1, \"caf\xc3").unwrap().is_empty());
+/// let values = feeder.feed(b"\xa9\"] {\"a\": tr").unwrap();
+/// assert_eq!(values, vec![Json::new(vec![Json::new(1), Json::new("café")])]);
+/// let values = feeder.feed(b"ue} 10").unwrap();
+/// assert_eq!(values.len(), 1);
+/// assert_eq!(feeder.finish().unwrap(), Some(Json::new(10)));
+/// ```
+///
+/// As with [crate::Jsons], a value that fails to parse is returned as
+/// an error value, refer [Json::is_error], and parsing continues with
+/// the next value. If a single value, or the whitespace and comments
+/// between two values, exceeds [Parser::max_bytes], the stream is closed
+/// with [Error::LimitExceeded]. Values completed before the limit was
+/// hit are returned first, and the error is returned by the next call
+/// to feed or finish.
+pub struct Feeder {
+    parser: Parser,
+    buffer: Vec<u8>,
+    // offset into buffer, upto which bytes are scanned.
+    off: usize,
+    // offset into buffer, where the current value starts.
+    start: Option<usize>,
+    nesting: usize,
+    quote: Option<u8>,
+    escape: bool,
+    comment: Option<Comment>,
+    // bytes of whitespace and comments since the last value, discarded
+    // from buffer.
+    skipped: usize,
+    closed: bool,
+    // error to be returned after the values completed before it.
+    error: Option<Error>,
+}
+
+#[derive(Clone, Copy)]
+enum Comment {
+    Line,
+    // whether the last scanned byte is '*'.
+    Block(bool),
+}
+
+impl Default for Feeder {
+    fn default() -> Feeder {
+        Feeder {
+            parser: Parser::new(),
+            buffer: Vec::with_capacity(1024),
+            off: 0,
+            start: None,
+            nesting: 0,
+            quote: None,
+            escape: false,
+            comment: None,
+            skipped: 0,
+            closed: false,
+            error: None,
+        }
+    }
+}
+
+impl Feeder {
+    /// Create a new push parser, with default [Parser] configuration.
+    pub fn new() -> Feeder {
+        Feeder::default()
+    }
+
+    /// Parse each JSON value in the stream using ``parser``, default
+    /// is [Parser::new].
+    pub fn parser(mut self, parser: Parser) -> Feeder {
+        self.parser = parser;
+        self
+    }
+
+    /// Feed the next ``chunk`` of JSON text, and return the values that
+    /// are completed by this chunk, in text order.
+    pub fn feed(&mut self, chunk: &[u8]) -> Result<Vec<Json>> {
+        if self.closed {
+            return self.error.take().map_or(Ok(vec![]), Err);
+        }
+        self.buffer.extend_from_slice(chunk);
+
+        let mut values = vec![];
+        let res = loop {
+            match self.scan() {
+                Some(end) => match self.check_value(end) {
+                    Ok(()) => values.push(self.parse(end)),
+                    Err(err) => break Err(err),
+                },
+                None => break self.check_value(self.buffer.len()),
+            }
+        };
+        match res {
+            Ok(()) => {
+                // discard whitespace and comments scanned before the value.
+                let n = self.start.unwrap_or(self.off);
+                self.buffer.drain(..n);
+                self.off -= n;
+                self.skipped += n;
+                if let Some(start) = self.start.as_mut() {
+                    *start = 0;
+                }
+                Ok(values)
+            }
+            Err(err) if values.is_empty() => Err(err),
+            Err(err) => {
+                self.error = Some(err);
+                Ok(values)
+            }
+        }
+    }
+
+    /// Close the stream, and return the pending value, if any. Pending
+    /// value that is incomplete is returned as an error value.
+    pub fn finish(mut self) -> Result<Option<Json>> {
+        if self.closed {
+            return self.error.take().map_or(Ok(None), Err);
+        }
+        let end = self.buffer.len();
+        self.check_value(end)?;
+        Ok(self.start.map(|_| self.parse(end)))
+    }
+
+    // Scan buffered bytes, and return the end offset of the first complete
+    // value, if any.
+    fn scan(&mut self) -> Option<usize> {
+        let json5 = self.parser.profile != Profile::Strict;
+        while let Some(b) = self.buffer.get(self.off).copied() {
+            let off = self.off;
+            match (self.comment, self.quote) {
+                (Some(Comment::Line), _) => {
+                    if b == b'\n' || b == b'\r' {
+                        self.comment = None;
+                    }
+                }
+                (Some(Comment::Block(star)), _) => {
+                    self.comment = match b {
+                        b'/' if star => None,
+                        b => Some(Comment::Block(b == b'*')),
+                    };
+                }
+                (None, Some(_)) if self.escape => self.escape = false,
+                (None, Some(_)) if b == b'\\' => self.escape = true,
+                (None, Some(q)) if b == q => {
+                    self.quote = None;
+                    if self.nesting == 0 {
+                        self.off += 1;
+                        return Some(self.off);
+                    }
+                }
+                (None, Some(_)) => (),
+                (None, None) => match b {
+                    b'/' if json5 => match self.buffer.get(off + 1) {
+                        Some(b'/') => self.comment = Some(Comment::Line),
+                        Some(b'*') => {
+                            // skip the '*', it cannot close the comment.
+                            self.comment = Some(Comment::Block(false));
+                            self.off += 1;
+                        }
+                        Some(_) => self.start_value(),
+                        // wait for the next byte.
+                        None => return None,
+                    },
+                    b' ' | b'\t' | b'\n' | b'\r' if self.nesting == 0 => {
+                        if self.start.is_some() {
+                            return Some(off);
+                        }
+                    }
+                    b'[' | b'{' | b'"' | b'\''
+                        if self.nesting == 0
+                            && self.start.is_some()
+                            && (json5 || b != b'\'') =>
+                    {
+                        // end of scalar value, that is not self delimiting.
+                        return Some(off);
+                    }
+                    b'[' | b'{' => {
+                        self.start_value();
+                        self.nesting += 1;
+                    }
+                    b']' | b'}' if self.nesting > 0 => {
+                        self.nesting -= 1;
+                        if self.nesting == 0 {
+                            self.off += 1;
+                            return Some(self.off);
+                        }
+                    }
+                    b'"' => {
+                        self.start_value();
+                        self.quote = Some(b);
+                    }
+                    b'\'' if json5 => {
+                        self.start_value();
+                        self.quote = Some(b);
+                    }
+                    _ => self.start_value(),
+                },
+            }
+            self.off += 1;
+        }
+        None
+    }
+
+    fn start_value(&mut self) {
+        if self.start.is_none() {
+            self.start = Some(self.off);
+        }
+    }
+
+    // Parse the value ending at ``end``, and discard the scanned text.
+    fn parse(&mut self, end: usize) -> Json {
+        let text = &self.buffer[self.start.unwrap_or(end)..end];
+        let value = match self.parser.parse_slice(text) {
+            Ok(value) => value,
+            Err(err) => Json::__Error(err),
+        };
+        self.buffer.drain(..end);
+        self.off = 0;
+        self.start = None;
+        self.nesting = 0;
+        self.quote = None;
+        self.escape = false;
+        self.comment = None;
+        self.skipped = 0;
+        value
+    }
+
+    // Stop buffering, and parsing, once a JSON value, or the whitespace
+    // and comments before it, exceeds max_bytes.
+    fn check_value(&mut self, end: usize) -> Result<()> {
+        let max_bytes = self.parser.max_bytes;
+        let start = self.start.unwrap_or(end);
+        let (text, what) = if self.skipped + start > max_bytes {
+            (&self.buffer[..start], "text between values")
+        } else if end - start > max_bytes {
+            (&self.buffer[start..end], "json value")
+        } else {
+            return Ok(());
+        };
+        let mut lex = Lex::new(0, 1, 1);
+        lex.skip_bytes(text);
+        self.buffer.clear();
+        self.closed = true;
+        let msg = format!("{} exceeds max_bytes {}", what, max_bytes);
+        err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)
+    }
+}
+
+#[cfg(test)]
+#[path = "feeder_test.rs"]
+mod feeder_test;
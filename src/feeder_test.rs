@@ -0,0 +1,124 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use super::*;
+
+fn feed_all(feeder: &mut Feeder, chunks: &[&[u8]]) -> Vec<Json> {
+    let mut values = vec![];
+    for chunk in chunks.iter() {
+        values.extend(feeder.feed(chunk).unwrap());
+    }
+    values
+}
+
+#[test]
+fn test_feeder_split() {
+    let text = concat!(
+        r#" {"a\u00e9\"": ["x\\", "y\n", 10, -2.5e3], "é😀": {}} "#,
+        "\n[]\t\"汉语 \\u0041\" null true 1 ",
+    );
+    let refs: Vec<Json> = vec![
+        text[..text.find('\n').unwrap()].parse().unwrap(),
+        Json::new::<Vec<Json>>(vec![]),
+        Json::new("汉语 A"),
+        Json::Null,
+        Json::new(true),
+        Json::new(1),
+    ];
+
+    let bytes = text.as_bytes();
+    for i in 0..bytes.len() {
+        for j in i..bytes.len() {
+            let mut feeder = Feeder::new();
+            let chunks = [&bytes[..i], &bytes[i..j], &bytes[j..]];
+            let values = feed_all(&mut feeder, &chunks);
+            assert_eq!(values, refs, "{} {}", i, j);
+            assert_eq!(feeder.finish().unwrap(), None);
+        }
+    }
+
+    let mut feeder = Feeder::new();
+    let chunks: Vec<&[u8]> = bytes.chunks(1).collect();
+    assert_eq!(feed_all(&mut feeder, &chunks), refs);
+}
+
+#[test]
+fn test_feeder_finish() {
+    let mut feeder = Feeder::new();
+    assert_eq!(feeder.feed(b"1 2").unwrap(), vec![Json::new(1)]);
+    assert_eq!(feeder.feed(b"3").unwrap(), vec![]);
+    assert_eq!(feeder.finish().unwrap(), Some(Json::new(23)));
+
+    let mut feeder = Feeder::new();
+    assert_eq!(feeder.feed(b"10[1]").unwrap(), vec![Json::new(10), Json::new(vec![1])]);
+    assert_eq!(feeder.finish().unwrap(), None);
+
+    let mut feeder = Feeder::new();
+    assert_eq!(feeder.feed(b"[1, {\"a\": \"b").unwrap(), vec![]);
+    assert!(feeder.finish().unwrap().unwrap().is_error());
+
+    let feeder = Feeder::new();
+    assert_eq!(feeder.finish().unwrap(), None);
+}
+
+#[test]
+fn test_feeder_json5() {
+    let mut feeder = Feeder::new();
+    let chunks: &[&[u8]] = &[b"// [\n{a: '}\\'', /* ] *", b"/ b: [1,]} /", b"* x */ 2 "];
+    let values = feed_all(&mut feeder, chunks);
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0], "{a: '}\\'', b: [1]}".parse::<Json>().unwrap());
+    assert_eq!(values[1], Json::new(2));
+
+    let mut feeder = Feeder::new().parser(Parser::new().profile(Profile::Strict));
+    let values = feeder.feed(b"[1, 2,] 'a' [3] ").unwrap();
+    assert_eq!(values.len(), 3);
+    assert!(values[0].is_error());
+    assert!(values[1].is_error());
+    assert_eq!(values[2], Json::new(vec![3]));
+}
+
+#[test]
+fn test_feeder_limits() {
+    let parser = Parser::new().max_depth(2).max_bytes(8);
+    let mut feeder = Feeder::new().parser(parser);
+
+    let values = feeder.feed(b"[1, 2]   [[[3]]]").unwrap();
+    assert_eq!(values[0], Json::new(vec![1, 2]));
+    assert!(values[1].is_error());
+
+    assert_eq!(feeder.feed(b" [1, 2, ").unwrap(), vec![]);
+    let err = feeder.feed(b"3]").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+    assert_eq!(feeder.feed(b" 1 ").unwrap(), vec![]);
+    assert_eq!(feeder.finish().unwrap(), None);
+
+    // values completed before the limit are not lost.
+    let mut feeder = Feeder::new().parser(Parser::new().max_bytes(8));
+    assert_eq!(feeder.feed(b"1 [1,2,3,4,5,6] ").unwrap(), vec![Json::new(1)]);
+    let err = feeder.feed(b"2 ").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+    assert_eq!(feeder.feed(b"3 ").unwrap(), vec![]);
+
+    let mut feeder = Feeder::new().parser(Parser::new().max_bytes(8));
+    assert_eq!(feeder.feed(b"1 [1,2,3,4,5,6]").unwrap(), vec![Json::new(1)]);
+    let err = feeder.finish().unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+
+    // whitespace and comments between values are not buffered, and count
+    // towards max_bytes.
+    let mut feeder = Feeder::new();
+    assert_eq!(feeder.feed(b"1 /* ").unwrap(), vec![Json::new(1)]);
+    for _ in 0..100 {
+        assert_eq!(feeder.feed(b"* / comment ").unwrap(), vec![]);
+    }
+    assert!(feeder.buffer.len() < 16);
+    assert_eq!(feeder.feed(b"*/ 2 ").unwrap(), vec![Json::new(2)]);
+
+    let mut feeder = Feeder::new().parser(Parser::new().max_bytes(8));
+    assert_eq!(feeder.feed(b"1 /* 12").unwrap(), vec![Json::new(1)]);
+    let err = feeder.feed(b"3456 */ 2").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+    let mut feeder = Feeder::new().parser(Parser::new().max_bytes(8));
+    assert_eq!(feeder.feed(b"1          ").unwrap(), vec![Json::new(1)]);
+    assert!(feeder.feed(b" ").is_err());
+}
@@ -0,0 +1,317 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::fmt::Write;
+use std::str::from_utf8;
+
+use crate::num::{self, Floating, Integral};
+use crate::{json, json::Json, parse, Error, Result};
+
+/// Mode enumerates the flavours of JSON text that [Format] can generate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    /// Generate JSON text as per [RFC-8259](https://tools.ietf.org/html/rfc8259).
+    /// Hexadecimal and other non-standard numbers are normalized, and
+    /// NaN, Infinity values are serialized as null, or fail with
+    /// [Error::InvalidNumber] if so configured.
+    Strict,
+    /// Generate JSON text as per [JSON5](https://json5.org) specification,
+    /// which is what the parser accepts. Property keys that are valid
+    /// identifiers are left unquoted, numbers are serialized as they were
+    /// parsed and NaN, Infinity values are serialized as such.
+    Json5,
+}
+
+/// Format type configures how a [Json] value is serialized into JSON
+/// text.
+///
+/// Default configuration generates strict JSON text, indents nested
+/// values with two spaces, uses `\n` for newline and adds a space after
+/// the colon that separates property key from its value:
+///
+/// ```
+/// use jsondata::{Format, Json, Mode};
+///
+/// let json: Json = r#"{"a": [1, 2], "b": {"c": null}}"#.parse().unwrap();
+///
+/// let format = Format::new().indent(4).compact_arrays(true);
+/// let text = json.to_string_with(&format).unwrap();
+/// assert_eq!(text, "{\n    \"a\": [1, 2],\n    \"b\": {\n        \"c\": null\n    }\n}");
+///
+/// let format = Format::compact().mode(Mode::Json5);
+/// let text = json.to_string_with(&format).unwrap();
+/// assert_eq!(text, "{a:[1,2],b:{c:null}}");
+/// ```
+///
+/// In strict mode, scalar values and property keys are encoded exactly
+/// the same way as the compact form generated by the [Display]
+/// implementation, hence pretty printed text is byte-compatible with
+/// compact text, modulo whitespace. Except for hexadecimal numbers and
+/// NaN, Infinity values, which are not valid JSON.
+///
+/// [Display]: std::fmt::Display
+#[derive(Clone, Debug)]
+pub struct Format {
+    mode: Mode,
+    pretty: bool,
+    indent: usize,
+    tabs: bool,
+    crlf: bool,
+    colon_space: bool,
+    compact_arrays: bool,
+    non_finite_null: bool,
+    single_quotes: bool,
+    trailing_commas: bool,
+}
+
+impl Default for Format {
+    fn default() -> Format {
+        Format {
+            mode: Mode::Strict,
+            pretty: true,
+            indent: 2,
+            tabs: false,
+            crlf: false,
+            colon_space: true,
+            compact_arrays: false,
+            non_finite_null: true,
+            single_quotes: false,
+            trailing_commas: false,
+        }
+    }
+}
+
+/// Following methods can be chained to configure the output format.
+impl Format {
+    /// Create a new format with default configuration.
+    pub fn new() -> Format {
+        Format::default()
+    }
+
+    /// Create a new format that generates single line text, without
+    /// any whitespace.
+    pub fn compact() -> Format {
+        Format {
+            pretty: false,
+            colon_space: false,
+            ..Format::default()
+        }
+    }
+
+    /// Flavour of JSON text to generate, default is [Mode::Strict].
+    pub fn mode(mut self, mode: Mode) -> Format {
+        self.mode = mode;
+        self
+    }
+
+    /// Number of spaces to use for each level of indentation. Ignored
+    /// when indenting with tabs.
+    pub fn indent(mut self, width: usize) -> Format {
+        self.indent = width;
+        self
+    }
+
+    /// Indent with one tab character for each level, instead of spaces.
+    pub fn tabs(mut self, tabs: bool) -> Format {
+        self.tabs = tabs;
+        self
+    }
+
+    /// Use `\r\n` as newline, instead of `\n`.
+    pub fn crlf(mut self, crlf: bool) -> Format {
+        self.crlf = crlf;
+        self
+    }
+
+    /// Add a space between the colon and the property value.
+    pub fn colon_space(mut self, colon_space: bool) -> Format {
+        self.colon_space = colon_space;
+        self
+    }
+
+    /// Print arrays, whose items are all scalar values, in a single line.
+    pub fn compact_arrays(mut self, compact_arrays: bool) -> Format {
+        self.compact_arrays = compact_arrays;
+        self
+    }
+
+    /// In strict mode, serialize NaN, Infinity and -Infinity as null.
+    /// Otherwise fail with [Error::InvalidNumber]. Default is true.
+    pub fn non_finite_null(mut self, non_finite_null: bool) -> Format {
+        self.non_finite_null = non_finite_null;
+        self
+    }
+
+    /// In JSON5 mode, quote strings and property keys with single quotes.
+    pub fn single_quotes(mut self, single_quotes: bool) -> Format {
+        self.single_quotes = single_quotes;
+        self
+    }
+
+    /// In JSON5 mode, add a comma after the last item of an array or
+    /// object that spans multiple lines.
+    pub fn trailing_commas(mut self, trailing_commas: bool) -> Format {
+        self.trailing_commas = trailing_commas;
+        self
+    }
+}
+
+impl Format {
+    /// Serialize ``json`` value into ``w`` using this format.
+    pub fn write<W: Write>(&self, w: &mut W, json: &Json) -> Result<()> {
+        self.write_value(w, json, 0)
+    }
+
+    // Same format, without newlines and indentation, refer Framing::Ndjson.
+    pub(crate) fn single_line(mut self) -> Format {
+        self.pretty = false;
+        self
+    }
+
+    fn write_value<W: Write>(&self, w: &mut W, json: &Json, depth: usize) -> Result<()> {
+        let json = json.resolve();
+        match json {
+            Json::Array(items) if items.is_empty() => put(w, "[]"),
+            Json::Array(items) if self.compact_arrays && is_scalars(items) => {
+                put(w, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        put(w, if self.pretty { ", " } else { "," })?;
+                    }
+                    self.write_scalar(w, item)?;
+                }
+                put(w, "]")
+            }
+            Json::Array(items) => {
+                put(w, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        put(w, ",")?;
+                    }
+                    self.write_newline(w, depth + 1)?;
+                    self.write_value(w, item, depth + 1)?;
+                }
+                self.write_trailing_comma(w)?;
+                self.write_newline(w, depth)?;
+                put(w, "]")
+            }
+            Json::Object(props) if props.is_empty() => put(w, "{}"),
+            Json::Object(props) => {
+                put(w, "{")?;
+                for (i, prop) in props.iter().enumerate() {
+                    if i > 0 {
+                        put(w, ",")?;
+                    }
+                    self.write_newline(w, depth + 1)?;
+                    self.write_key(w, prop.as_key())?;
+                    put(w, if self.colon_space { ": " } else { ":" })?;
+                    self.write_value(w, prop.as_value(), depth + 1)?;
+                }
+                self.write_trailing_comma(w)?;
+                self.write_newline(w, depth)?;
+                put(w, "}")
+            }
+            _ => self.write_scalar(w, json),
+        }
+    }
+
+    fn write_scalar<W: Write>(&self, w: &mut W, json: &Json) -> Result<()> {
+        match json {
+            Json::Integer(Integral::Text { len, bytes }) => {
+                let text = from_utf8(&bytes[..*len]).unwrap();
+                if self.mode == Mode::Json5 || num::is_json_number(text) {
+                    put(w, text)
+                } else {
+                    let value = json.to_integer_result()?;
+                    err_at!(IoError, write!(w, "{}", value))
+                }
+            }
+            Json::Float(Floating::Text { len, bytes }) => {
+                let text = from_utf8(&bytes[..*len]).unwrap();
+                if self.mode == Mode::Json5 || num::is_json_number(text) {
+                    put(w, text)
+                } else {
+                    self.write_float(w, json.to_float_result()?)
+                }
+            }
+            Json::Float(Floating::Data { value }) => self.write_float(w, *value),
+            Json::String(s) => self.write_string(w, s),
+            _ => err_at!(IoError, write!(w, "{}", json)),
+        }
+    }
+
+    fn write_float<W: Write>(&self, w: &mut W, value: f64) -> Result<()> {
+        match self.mode {
+            _ if value.is_finite() => err_at!(IoError, write!(w, "{:e}", value)),
+            Mode::Json5 if value.is_nan() => put(w, "NaN"),
+            Mode::Json5 if value.is_sign_positive() => put(w, "Infinity"),
+            Mode::Json5 => put(w, "-Infinity"),
+            Mode::Strict if self.non_finite_null => put(w, "null"),
+            Mode::Strict => {
+                err_at!(InvalidNumber, msg: "{} not allowed in strict mode", value)
+            }
+        }
+    }
+
+    fn write_key<W: Write>(&self, w: &mut W, key: &str) -> Result<()> {
+        if self.mode == Mode::Json5 && is_identifier(key) {
+            put(w, key)
+        } else {
+            self.write_string(w, key)
+        }
+    }
+
+    fn write_string<W: Write>(&self, w: &mut W, val: &str) -> Result<()> {
+        if self.mode == Mode::Json5 && self.single_quotes {
+            err_at!(IoError, json::encode_quoted(w, val, '\''))
+        } else {
+            err_at!(IoError, json::encode_string(w, val))
+        }
+    }
+
+    fn write_trailing_comma<W: Write>(&self, w: &mut W) -> Result<()> {
+        if self.mode == Mode::Json5 && self.pretty && self.trailing_commas {
+            put(w, ",")?;
+        }
+        Ok(())
+    }
+
+    fn write_newline<W: Write>(&self, w: &mut W, depth: usize) -> Result<()> {
+        if !self.pretty {
+            return Ok(());
+        }
+
+        put(w, if self.crlf { "\r\n" } else { "\n" })?;
+        for _ in 0..depth {
+            if self.tabs {
+                put(w, "\t")?;
+            } else {
+                for _ in 0..self.indent {
+                    put(w, " ")?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn put<W: Write>(w: &mut W, s: &str) -> Result<()> {
+    err_at!(IoError, w.write_str(s))
+}
+
+fn is_scalars(items: &[Json]) -> bool {
+    items.iter().all(|item| !matches!(item, Json::Array(_) | Json::Object(_)))
+}
+
+// Property keys can be left unquoted, if they are valid ECMAScript
+// IdentifierName, same as accepted by the JSON5 parser.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    match chars.next() {
+        Some(ch) if parse::is_id_start(ch) => chars.all(parse::is_id_part),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+#[path = "format_test.rs"]
+mod format_test;
@@ -0,0 +1,154 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use crate::format::{Format, Mode};
+use crate::{json::Json, property::Property, Error};
+
+#[test]
+fn test_pretty_default() {
+    let text = r#"{"a": [1, true, "x\ty"], "b": {"c": null, "d": []}, "e": {}}"#;
+    let json: Json = text.parse().unwrap();
+
+    let refs = concat!(
+        "{\n",
+        "  \"a\": [\n",
+        "    1,\n",
+        "    true,\n",
+        "    \"x\\ty\"\n",
+        "  ],\n",
+        "  \"b\": {\n",
+        "    \"c\": null,\n",
+        "    \"d\": []\n",
+        "  },\n",
+        "  \"e\": {}\n",
+        "}"
+    );
+    assert_eq!(json.to_pretty_string(), refs);
+}
+
+#[test]
+fn test_pretty_options() {
+    let json: Json = r#"{"a": [1, 2], "b": [[3], {"c": 4}]}"#.parse().unwrap();
+
+    let format = Format::new().tabs(true).crlf(true).colon_space(false);
+    let refs = concat!(
+        "{\r\n",
+        "\t\"a\":[\r\n\t\t1,\r\n\t\t2\r\n\t],\r\n",
+        "\t\"b\":[\r\n\t\t[\r\n\t\t\t3\r\n\t\t],\r\n",
+        "\t\t{\r\n\t\t\t\"c\":4\r\n\t\t}\r\n\t]\r\n",
+        "}"
+    );
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+
+    let format = Format::new().indent(0).compact_arrays(true);
+    let refs =
+        concat!("{\n", "\"a\": [1, 2],\n", "\"b\": [\n[3],\n{\n\"c\": 4\n}\n]\n", "}");
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+}
+
+#[test]
+fn test_pretty_compatible() {
+    let jsons = include!("../testdata/test_simple.jsons");
+    let formats = [
+        Format::new(),
+        Format::new().indent(4).compact_arrays(true),
+        Format::new().tabs(true).crlf(true),
+    ];
+
+    for (i, text) in jsons.iter().enumerate() {
+        let json: Json = text.parse().unwrap();
+        let compact = json.to_string_with(&Format::compact()).unwrap();
+        for format in formats.iter() {
+            let pretty = json.to_string_with(format).unwrap();
+            let pretty: String = strip_whitespace(&pretty);
+            assert_eq!(pretty, strip_whitespace(&compact), "testcase {}", i);
+        }
+    }
+}
+
+fn strip_whitespace(text: &str) -> String {
+    let (mut out, mut in_string, mut escape) = (String::new(), false, false);
+    for ch in text.chars() {
+        match ch {
+            _ if escape => escape = false,
+            '\\' if in_string => escape = true,
+            '"' => in_string = !in_string,
+            ch if !in_string && ch.is_whitespace() => continue,
+            _ => (),
+        }
+        out.push(ch);
+    }
+    out
+}
+
+#[test]
+fn test_strict_mode() {
+    let json: Json = "[0x1F, -0x10, +5, 007, .5, 1., 1.5e2, NaN, Infinity, -Infinity]"
+        .parse()
+        .unwrap();
+
+    let refs = "[31,-16,5,7,5e-1,1e0,1.5e2,null,null,null]";
+    assert_eq!(json.to_string_with(&Format::compact()).unwrap(), refs);
+
+    let format = Format::compact().non_finite_null(false);
+    match json.to_string_with(&format) {
+        Err(Error::InvalidNumber(_, msg)) => assert!(msg.contains("NaN"), "{}", msg),
+        res => panic!("unexpected {:?}", res),
+    }
+
+    let json: Json = r#"{"a": "it's", "b c": "say \"hi\""}"#.parse().unwrap();
+    let format = Format::compact().single_quotes(true);
+    let refs = r#"{"a":"it's","b c":"say \"hi\""}"#;
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+}
+
+#[test]
+fn test_json5_mode() {
+    let text = r#"{"a": [0x1F, NaN, -Infinity, "it's"], "b c": {"d1": "say \"hi\""}}"#;
+    let json: Json = text.parse().unwrap();
+
+    let format = Format::compact().mode(Mode::Json5);
+    let refs = r#"{a:[0x1F,NaN,-Infinity,"it's"],"b c":{d1:"say \"hi\""}}"#;
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+
+    let format = Format::compact().mode(Mode::Json5).single_quotes(true);
+    let refs = r#"{a:[0x1F,NaN,-Infinity,'it\'s'],'b c':{d1:'say "hi"'}}"#;
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+
+    let format =
+        Format::new().mode(Mode::Json5).trailing_commas(true).compact_arrays(true);
+    let refs = concat!(
+        "{\n",
+        "  a: [0x1F, NaN, -Infinity, \"it's\"],\n",
+        "  \"b c\": {\n",
+        "    d1: \"say \\\"hi\\\"\",\n",
+        "  },\n",
+        "}"
+    );
+    assert_eq!(json.to_string_with(&format).unwrap(), refs);
+
+    let value: Json = json.to_string_with(&format).unwrap().parse().unwrap();
+    assert_eq!(value.to_string(), json.to_string());
+}
+
+#[test]
+fn test_is_identifier() {
+    let json: Json = Json::new(vec![
+        Property::new("_a", Json::Null),
+        Property::new("$b1", Json::Null),
+        Property::new("1c", Json::Null),
+        Property::new("d-e", Json::Null),
+        Property::new("", Json::Null),
+        Property::new("ñ", Json::Null),
+        Property::new("\u{345}x", Json::Null),
+        Property::new("x\u{345}\u{200d}", Json::Null),
+    ]);
+    let format = Format::compact().mode(Mode::Json5);
+    let refs = concat!(
+        r#"{"":null,$b1:null,"1c":null,_a:null,"d-e":null,"#,
+        "x\u{345}\u{200d}:null,ñ:null,\"\u{345}x\":null}"
+    );
+    let text = json.to_string_with(&format).unwrap();
+    assert_eq!(text, refs);
+    let parser = crate::Parser::new().profile(crate::Profile::Json5);
+    assert_eq!(parser.parse(&text).unwrap(), json);
+}
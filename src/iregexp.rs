@@ -0,0 +1,281 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+// Module iregexp implements [I-Regexp RFC spec.], the interoperable regular
+// expression format, used by match() and search() functions of JSONPath.
+//
+// Patterns are validated against the I-Regexp grammar, and translated into
+// the syntax of the regex crate, as suggested by section 5 of the spec.
+// Matching takes linear time in the length of text, and unicode character
+// class escapes, ``\p{..}`` and ``\P{..}``, support every General Category
+// listed by the spec.
+//
+// [I-Regexp RFC spec.]: https://www.rfc-editor.org/rfc/rfc9485
+
+use crate::{Error, Result};
+
+// Groups nested deeper than this are rejected. Along with quantifiers
+// on each group, this stays within the nesting limit of regex crate.
+const MAX_NESTING: usize = 100;
+
+// General Categories allowed in ``\p{..}`` and ``\P{..}``.
+const CATEGORIES: [&str; 36] = [
+    "L", "Lu", "Ll", "Lt", "Lm", "Lo", "M", "Mn", "Mc", "Me", "N", "Nd", "Nl", "No", "P",
+    "Pc", "Pd", "Ps", "Pe", "Pi", "Pf", "Po", "Z", "Zs", "Zl", "Zp", "S", "Sm", "Sc",
+    "Sk", "So", "C", "Cc", "Cf", "Co", "Cn",
+];
+
+#[derive(Clone, Debug)]
+pub(crate) struct Regex {
+    re: regex::Regex,
+}
+
+#[derive(Clone, Debug)]
+enum Atom {
+    Char(char),
+    // (negated, items)
+    Class(bool, Vec<Item>),
+}
+
+#[derive(Clone, Debug)]
+enum Item {
+    // ``.``, any character except newline and carriage return.
+    Any,
+    Range(char, char),
+    // (category, complement)
+    Category(&'static str, bool),
+}
+
+enum Escape {
+    Char(char),
+    Category(&'static str, bool),
+}
+
+impl Regex {
+    // Compile ``pattern``, to match the whole text if ``full`` is true,
+    // like match() function, or any substring of text, like search().
+    //
+    // Pattern is translated in a single pass, groups are tracked by their
+    // nesting depth, so that deeply nested patterns don't exhaust the stack.
+    pub(crate) fn new(pattern: &str, full: bool) -> Result<Regex> {
+        let cs: Vec<char> = pattern.chars().collect();
+        let (mut off, mut depth) = (0, 0);
+        let mut text = String::new();
+        while let Some(ch) = cs.get(off) {
+            match ch {
+                '|' => text.push('|'),
+                '(' if depth >= MAX_NESTING => {
+                    err_at!(JpathFail, msg: "regexp nested too deep")?
+                }
+                '(' => {
+                    depth += 1;
+                    text.push_str("(?:");
+                }
+                ')' if depth == 0 => err_at!(JpathFail, msg: "unbalanced ) in regexp")?,
+                ')' => {
+                    depth -= 1;
+                    off += 1;
+                    text.push(')');
+                    parse_quantifier(&cs, &mut off, &mut text)?;
+                    continue;
+                }
+                _ => {
+                    translate_atom(&parse_atom(&cs, &mut off)?, &mut text);
+                    parse_quantifier(&cs, &mut off, &mut text)?;
+                    continue;
+                }
+            }
+            off += 1;
+        }
+        if depth > 0 {
+            err_at!(JpathFail, msg: "unbalanced ( in regexp")?;
+        }
+
+        let text = match full {
+            true => format!("\\A(?:{})\\z", text),
+            false => text,
+        };
+        let re = err_at!(JpathFail, regex::Regex::new(&text))?;
+        Ok(Regex { re })
+    }
+
+    // Return true if ``text`` matches the pattern.
+    pub(crate) fn is_match(&self, text: &str) -> bool {
+        self.re.is_match(text)
+    }
+}
+
+// Parse optional quantifier following an atom or a group, and translate
+// it into ``{min,max}``.
+fn parse_quantifier(cs: &[char], off: &mut usize, text: &mut String) -> Result<()> {
+    let (min, max) = match cs.get(*off) {
+        Some('*') => (0, None),
+        Some('+') => (1, None),
+        Some('?') => (0, Some(1)),
+        Some('{') => {
+            *off += 1;
+            let min = parse_count(cs, off)?;
+            let max = match cs.get(*off) {
+                Some(',') if cs.get(*off + 1) == Some(&'}') => {
+                    *off += 1;
+                    None
+                }
+                Some(',') => {
+                    *off += 1;
+                    Some(parse_count(cs, off)?)
+                }
+                _ => Some(min),
+            };
+            match cs.get(*off) {
+                Some('}') if max.is_none_or(|max| min <= max) => (min, max),
+                _ => err_at!(JpathFail, msg: "invalid quantifier in regexp")?,
+            }
+        }
+        _ => return Ok(()),
+    };
+    *off += 1;
+    match max {
+        Some(max) => text.push_str(&format!("{{{},{}}}", min, max)),
+        None => text.push_str(&format!("{{{},}}", min)),
+    }
+    Ok(())
+}
+
+fn parse_count(cs: &[char], off: &mut usize) -> Result<usize> {
+    let start = *off;
+    while cs.get(*off).is_some_and(|ch| ch.is_ascii_digit()) {
+        *off += 1;
+    }
+    let digits: String = cs[start..*off].iter().collect();
+    err_at!(JpathFail, digits.parse::<usize>())
+}
+
+fn parse_atom(cs: &[char], off: &mut usize) -> Result<Atom> {
+    let ch = cs[*off];
+    *off += 1;
+    let atom = match ch {
+        '.' => Atom::Class(false, vec![Item::Any]),
+        '[' => parse_class(cs, off)?,
+        '\\' => match parse_escape(cs, off)? {
+            Escape::Char(ch) => Atom::Char(ch),
+            Escape::Category(cat, compl) => {
+                Atom::Class(false, vec![Item::Category(cat, compl)])
+            }
+        },
+        '*' | '+' | '?' | ']' | '{' | '}' => {
+            err_at!(JpathFail, msg: "unexpected {:?} in regexp", ch)?
+        }
+        ch => Atom::Char(ch),
+    };
+    Ok(atom)
+}
+
+fn parse_escape(cs: &[char], off: &mut usize) -> Result<Escape> {
+    let ch = match cs.get(*off) {
+        Some(ch) => *ch,
+        None => err_at!(JpathFail, msg: "incomplete escape in regexp")?,
+    };
+    *off += 1;
+    let esc = match ch {
+        'n' => Escape::Char('\n'),
+        'r' => Escape::Char('\r'),
+        't' => Escape::Char('\t'),
+        '(' | ')' | '*' | '+' | '-' | '.' | '?' | '[' | '\\' | ']' | '^' | '{' | '|'
+        | '}' => Escape::Char(ch),
+        'p' | 'P' if cs.get(*off) == Some(&'{') => {
+            let end = match cs[*off..].iter().position(|ch| *ch == '}') {
+                Some(n) => *off + n,
+                None => err_at!(JpathFail, msg: "incomplete category in regexp")?,
+            };
+            let name: String = cs[*off + 1..end].iter().collect();
+            *off = end + 1;
+            match CATEGORIES.iter().find(|cat| **cat == name) {
+                Some(cat) => Escape::Category(cat, ch == 'P'),
+                None => err_at!(JpathFail, msg: "unknown category {:?}", name)?,
+            }
+        }
+        _ => err_at!(JpathFail, msg: "invalid escape \\{} in regexp", ch)?,
+    };
+    Ok(esc)
+}
+
+fn parse_class(cs: &[char], off: &mut usize) -> Result<Atom> {
+    let negated = cs.get(*off) == Some(&'^');
+    if negated {
+        *off += 1;
+    }
+    let mut items = vec![];
+    loop {
+        let (first, next) = (items.is_empty(), cs.get(*off + 1));
+        let lo = match cs.get(*off) {
+            Some(']') if !first => {
+                *off += 1;
+                break;
+            }
+            Some('-') if first || next == Some(&']') => {
+                *off += 1;
+                '-'
+            }
+            Some(_) => match parse_class_char(cs, off)? {
+                Escape::Char(ch) => ch,
+                Escape::Category(cat, compl) => {
+                    items.push(Item::Category(cat, compl));
+                    continue;
+                }
+            },
+            None => err_at!(JpathFail, msg: "unbalanced [ in regexp")?,
+        };
+        let hi = match (cs.get(*off), cs.get(*off + 1)) {
+            (Some('-'), Some(ch)) if *ch != ']' => {
+                *off += 1;
+                match parse_class_char(cs, off)? {
+                    Escape::Char(hi) if lo <= hi => hi,
+                    _ => err_at!(JpathFail, msg: "invalid range in regexp")?,
+                }
+            }
+            _ => lo,
+        };
+        items.push(Item::Range(lo, hi));
+    }
+    Ok(Atom::Class(negated, items))
+}
+
+fn parse_class_char(cs: &[char], off: &mut usize) -> Result<Escape> {
+    let ch = cs[*off];
+    *off += 1;
+    match ch {
+        '\\' => parse_escape(cs, off),
+        '[' | ']' | '-' => err_at!(JpathFail, msg: "unexpected {:?} in regexp", ch),
+        ch => Ok(Escape::Char(ch)),
+    }
+}
+
+// Translate an atom into the syntax of regex crate.
+fn translate_atom(atom: &Atom, text: &mut String) {
+    let mut buf = [0; 4];
+    match atom {
+        Atom::Char(ch) => text.push_str(&regex::escape(ch.encode_utf8(&mut buf))),
+        Atom::Class(negated, items) => {
+            text.push_str(if *negated { "[^" } else { "[" });
+            for item in items.iter() {
+                match item {
+                    Item::Any => text.push_str("^\\n\\r"),
+                    Item::Range(lo, hi) => {
+                        text.push_str(&regex::escape(lo.encode_utf8(&mut buf)));
+                        text.push('-');
+                        text.push_str(&regex::escape(hi.encode_utf8(&mut buf)));
+                    }
+                    Item::Category(cat, compl) => {
+                        text.push_str(if *compl { "\\P{" } else { "\\p{" });
+                        text.push_str(cat);
+                        text.push('}');
+                    }
+                }
+            }
+            text.push(']');
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "iregexp_test.rs"]
+mod iregexp_test;
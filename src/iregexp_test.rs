@@ -0,0 +1,99 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use super::*;
+
+#[test]
+fn test_iregexp_match() {
+    let testcases = [
+        ("abc", "abc", true),
+        ("abc", "abcd", false),
+        ("a.c", "a\u{e9}c", true),
+        ("a.c", "a\nc", false),
+        ("a*", "", true),
+        ("a+b?", "aaab", true),
+        ("a{2,3}", "aaaa", false),
+        ("a{2,}", "aaaa", true),
+        ("a{2}", "aa", true),
+        ("(ab|cd)+", "abcdab", true),
+        ("(a|ab)(c|bcd)", "abcd", true),
+        ("(a*)*b", "aaab", true),
+        ("[a-c]+", "abcabc", true),
+        ("[^a-c]", "d", true),
+        ("[^a-c]", "b", false),
+        ("[-a]+", "-a-", true),
+        ("[a-]+", "-a-", true),
+        ("\\.\\*", ".*", true),
+        ("[\\]\\-]+", "]-", true),
+        ("\\p{Lu}\\p{Ll}+", "Hello", true),
+        ("\\p{L}+", "\u{e9}t\u{e9}", true),
+        ("\\P{N}", "1", false),
+        ("[\\p{N}x]+", "1x\u{663}", true),
+        ("^$", "^$", true),
+        ("\\p{Nd}+", "0\u{663}", true),
+        ("\\p{Nd}", "\u{2167}", false),
+        ("\\p{P}\\p{S}\\p{Z}", "!$ ", true),
+        ("\\p{Sc}\\p{Zs}\\p{Pd}", "\u{20ac}\u{a0}-", true),
+        ("\\p{L}", "\u{345}", false),
+        ("\\p{Mn}", "\u{345}", true),
+        ("\\p{Cn}", "\u{378}", true),
+        ("[^\\P{Lt}]", "\u{1c5}", true),
+    ];
+    for (pattern, text, res) in testcases.iter() {
+        let re = Regex::new(pattern, true).unwrap();
+        assert_eq!(re.is_match(text), *res, "{} {:?}", pattern, text);
+    }
+}
+
+#[test]
+fn test_iregexp_search() {
+    let re = Regex::new("b.?d", false).unwrap();
+    assert!(re.is_match("abcde"));
+    assert!(re.is_match("bd"));
+    assert!(!re.is_match("bxxd"));
+    assert!(Regex::new("", false).unwrap().is_match("abc"));
+    assert!(!Regex::new("", true).unwrap().is_match("abc"));
+    assert!(Regex::new("^", false).unwrap().is_match("a^b"));
+    assert!(!Regex::new("$", false).unwrap().is_match("ab"));
+
+    let testcases = [
+        "(a",
+        "a)",
+        "[a",
+        "[]",
+        "a{3,2}",
+        "*",
+        "\\d",
+        "\\p{Xx}",
+        "\\p{Lc}",
+        "\\p{Greek}",
+        "\\p{Nd",
+        "[z-a]",
+    ];
+    for pattern in testcases.iter() {
+        assert!(Regex::new(pattern, true).is_err(), "{}", pattern);
+        assert!(Regex::new(pattern, false).is_err(), "{}", pattern);
+    }
+}
+
+#[test]
+fn test_iregexp_pathological() {
+    let text = "a".repeat(10_000);
+    let re = Regex::new("(a|a)*b", true).unwrap();
+    assert!(!re.is_match(&text));
+    let re = Regex::new("(a*)*(a|aa)*b", false).unwrap();
+    assert!(!re.is_match(&text));
+    let re = Regex::new("(a|a)*", true).unwrap();
+    assert!(re.is_match(&text));
+
+    let text = "x".repeat(200_000);
+    assert!(Regex::new(".*", true).unwrap().is_match(&text));
+    assert!(Regex::new("[^\\n]*x", true).unwrap().is_match(&text));
+    assert!(!Regex::new(".*y", false).unwrap().is_match(&text));
+
+    let nested = format!("{}a{}", "(".repeat(100), ")*".repeat(100));
+    assert!(Regex::new(&nested, true).unwrap().is_match("aaa"));
+    let nested = format!("{}a{}", "(".repeat(101), ")".repeat(101));
+    assert!(Regex::new(&nested, true).is_err());
+    let nested = format!("{}a{}", "(".repeat(100_000), ")".repeat(100_000));
+    assert!(Regex::new(&nested, true).is_err());
+}
@@ -4,12 +4,382 @@
 //!
 //! [JSON Pointer RFC spec.]: https://tools.ietf.org/html/rfc6901
 
-use crate::{json::Json, ops, Error, Result};
+use std::{borrow::Cow, fmt, iter::FromIterator, str::FromStr};
+
+use crate::{json::Json, ops, parse::Parser, Error, Result};
+
+/// Pointer is a parsed [JSON Pointer][rfc], a list of unescaped path
+/// fragments. Parse a pointer once, and use it for any number of
+/// lookups, [Json::get], [Json::set], [Json::delete], [Json::append],
+/// that otherwise parse their ``path`` argument on every call.
+///
+/// ```
+/// use jsondata::{jptr::Pointer, Json};
+///
+/// let json: Json = r#"{"a/b": [{"c": 10}, {"c": 20}]}"#.parse().unwrap();
+/// let mut ptr: Pointer = "/a~1b".parse().unwrap();
+/// ptr.push("1");
+/// ptr.push("c");
+/// assert_eq!(json.get(&ptr).unwrap(), Json::new(20));
+/// assert_eq!(ptr.to_string(), "/a~1b/1/c");
+/// assert_eq!(ptr.parent().unwrap().to_string(), "/a~1b/1");
+/// ```
+///
+/// Pointer is parsed from string, and displayed, strictly as per RFC
+/// 6901, escaping only ``~`` and ``/`` in fragments, hence displayed
+/// pointer parses back to the same pointer. String paths passed to
+/// [Json::get] and other CRUD methods also honour backslash escapes of
+/// [quote], for compatibility. Pointers can also be represented as URI
+/// fragment, like ``#/a%20b``, refer [Pointer::from_uri_fragment] and
+/// [Pointer::to_uri_fragment].
+///
+/// Array index ``-``, refer to the (nonexistent) element after the last
+/// array element, hence [Json::set] and [Json::append] with ``-`` add a
+/// new element to the end of array. By default negative indices count
+/// from the end of array, use [Pointer::strict] to reject them.
+///
+/// [rfc]: https://tools.ietf.org/html/rfc6901
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pointer {
+    frags: Vec<String>,
+    strict: bool,
+}
+
+impl Pointer {
+    /// Create a pointer to the whole document, ``""``.
+    pub fn root() -> Pointer {
+        Pointer::default()
+    }
+
+    /// Parse a pointer represented as URI fragment identifier, like
+    /// ``#/a~1b/c%20d``. Fragments are percent-decoded and unescaped as
+    /// per RFC 6901, backslash escapes are not allowed.
+    pub fn from_uri_fragment(text: &str) -> Result<Pointer> {
+        let path = match text.strip_prefix('#') {
+            Some(path) => percent_decode(path)?,
+            None => err_at!(JptrFail, msg: "uri fragment should start with #")?,
+        };
+        Ok(Pointer { frags: rfc_fragments(&path)?, strict: false })
+    }
+
+    /// Represent pointer as URI fragment identifier, fragments are escaped
+    /// as per RFC 6901 and percent-encoded as per RFC 3986.
+    pub fn to_uri_fragment(&self) -> String {
+        let mut outs = "#".to_string();
+        for frag in self.frags.iter() {
+            outs.push('/');
+            let frag = frag.replace('~', "~0").replace('/', "~1");
+            for b in frag.bytes() {
+                match b {
+                    b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' => outs.push(b as char),
+                    b'-' | b'.' | b'_' | b'~' | b'!' | b'$' | b'&' | b'\'' => {
+                        outs.push(b as char)
+                    }
+                    b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b':' | b'@' => {
+                        outs.push(b as char)
+                    }
+                    b'?' => outs.push('?'),
+                    b => outs.push_str(&format!("%{:02X}", b)),
+                }
+            }
+        }
+        outs
+    }
+
+    /// Lookup array elements strictly as per RFC 6901, only ``-`` and
+    /// indices without leading zeros are allowed, negative indices are
+    /// rejected with [Error::InvalidIndex]. Default is false.
+    pub fn strict(mut self, strict: bool) -> Pointer {
+        self.strict = strict;
+        self
+    }
+
+    /// Return true if pointer is strict, refer [Pointer::strict].
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Return the number of fragments in the pointer.
+    pub fn len(&self) -> usize {
+        self.frags.len()
+    }
+
+    /// Return true if pointer points to the whole document.
+    pub fn is_empty(&self) -> bool {
+        self.frags.is_empty()
+    }
+
+    /// Return the unescaped fragments in the pointer.
+    pub fn as_fragments(&self) -> &[String] {
+        &self.frags
+    }
+
+    /// Return the last fragment, the key or index, of the pointer.
+    pub fn last(&self) -> Option<&str> {
+        self.frags.last().map(|frag| frag.as_str())
+    }
+
+    /// Append an unescaped fragment, key or index, to the pointer.
+    pub fn push<S>(&mut self, frag: S)
+    where
+        S: Into<String>,
+    {
+        self.frags.push(frag.into())
+    }
+
+    /// Remove the last fragment from the pointer and return the same.
+    pub fn pop(&mut self) -> Option<String> {
+        self.frags.pop()
+    }
+
+    /// Return the pointer to the parent container, None for root.
+    pub fn parent(&self) -> Option<Pointer> {
+        let (_, frags) = self.frags.split_last()?;
+        Some(Pointer { frags: frags.to_vec(), strict: self.strict })
+    }
+
+    /// Return a new pointer, by appending fragments in ``other`` to
+    /// this pointer.
+    pub fn join(&self, other: &Pointer) -> Pointer {
+        let frags = self.frags.iter().chain(other.frags.iter()).cloned();
+        Pointer { frags: frags.collect(), strict: self.strict }
+    }
+}
+
+impl<S> FromIterator<S> for Pointer
+where
+    S: Into<String>,
+{
+    fn from_iter<T>(iter: T) -> Pointer
+    where
+        T: IntoIterator<Item = S>,
+    {
+        Pointer {
+            frags: iter.into_iter().map(|frag| frag.into()).collect(),
+            strict: false,
+        }
+    }
+}
+
+impl FromStr for Pointer {
+    type Err = Error;
+
+    fn from_str(path: &str) -> Result<Pointer> {
+        match path.strip_prefix('#') {
+            Some(_) => Pointer::from_uri_fragment(path),
+            None => Ok(Pointer { frags: rfc_fragments(path)?, strict: false }),
+        }
+    }
+}
+
+impl fmt::Display for Pointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for frag in self.frags.iter() {
+            write!(f, "/{}", frag.replace('~', "~0").replace('/', "~1"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Types that can be used as JSON pointer, for looking up a document.
+/// Implemented for string types, that are parsed on every lookup, and
+/// for [Pointer], that is already parsed.
+pub trait AsPointer {
+    /// Return the parsed pointer.
+    fn as_pointer(&self) -> Result<Cow<'_, Pointer>>;
+}
+
+impl AsPointer for Pointer {
+    fn as_pointer(&self) -> Result<Cow<'_, Pointer>> {
+        Ok(Cow::Borrowed(self))
+    }
+}
+
+// String paths also honour backslash escapes, unlike Pointer::from_str.
+impl AsPointer for str {
+    fn as_pointer(&self) -> Result<Cow<'_, Pointer>> {
+        let ptr = match self.strip_prefix('#') {
+            Some(_) => Pointer::from_uri_fragment(self)?,
+            None => Pointer { frags: fragments_of(self)?, strict: false },
+        };
+        Ok(Cow::Owned(ptr))
+    }
+}
+
+impl AsPointer for String {
+    fn as_pointer(&self) -> Result<Cow<'_, Pointer>> {
+        self.as_str().as_pointer()
+    }
+}
+
+impl<T> AsPointer for &T
+where
+    T: AsPointer + ?Sized,
+{
+    fn as_pointer(&self) -> Result<Cow<'_, Pointer>> {
+        (**self).as_pointer()
+    }
+}
+
+/// RelativePointer is a parsed [Relative JSON Pointer][rel], that refers
+/// to a field relative to a current location, given as [Pointer], within
+/// a document. Relative pointer has the form ``<up>[<shift>](<pointer>|#)``
+///
+/// * ``up``, number of levels to go up from the current location.
+/// * ``shift``, like ``+1`` or ``-2``, move to a sibling array element.
+/// * ``pointer``, JSON pointer to resolve from the location reached so far.
+/// * ``#``, instead of the field, return its key, or array index.
+///
+/// ```
+/// use jsondata::{jptr::{Pointer, RelativePointer}, Json};
+///
+/// let json: Json = r#"{"items": [{"id": 1}, {"id": 2}]}"#.parse().unwrap();
+/// let base: Pointer = "/items/0/id".parse().unwrap();
+///
+/// let rel: RelativePointer = "1+1/id".parse().unwrap();
+/// assert_eq!(rel.resolve(&json, &base).unwrap(), Json::new(2));
+/// assert_eq!(rel.to_pointer(&json, &base).unwrap().to_string(), "/items/1/id");
+/// let rel: RelativePointer = "2#".parse().unwrap();
+/// assert_eq!(rel.resolve(&json, &base).unwrap(), Json::new("items"));
+/// ```
+///
+/// [rel]: https://datatracker.ietf.org/doc/html/draft-bhutton-relative-json-pointer-00
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RelativePointer {
+    up: usize,
+    shift: Option<isize>,
+    // None for "#", key or index of the location.
+    ptr: Option<Pointer>,
+}
+
+impl FromStr for RelativePointer {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<RelativePointer> {
+        let (up, text) = parse_number(text)?;
+        let (shift, text) = match text.as_bytes().first() {
+            Some(b'+') => {
+                let (n, text) = parse_number(&text[1..])?;
+                (Some(err_at!(JptrFail, isize::try_from(n))?), text)
+            }
+            Some(b'-') => {
+                let (n, text) = parse_number(&text[1..])?;
+                (Some(-err_at!(JptrFail, isize::try_from(n))?), text)
+            }
+            _ => (None, text),
+        };
+        let ptr = match text {
+            "#" => None,
+            text if text.is_empty() || text.starts_with('/') => Some(text.parse()?),
+            text => err_at!(JptrFail, msg: "invalid relative pointer suffix {:?}", text)?,
+        };
+        Ok(RelativePointer { up, shift, ptr })
+    }
+}
+
+impl fmt::Display for RelativePointer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.up)?;
+        match self.shift {
+            Some(shift) if shift < 0 => write!(f, "{}", shift)?,
+            Some(shift) => write!(f, "+{}", shift)?,
+            None => (),
+        }
+        match &self.ptr {
+            Some(ptr) => write!(f, "{}", ptr),
+            None => write!(f, "#"),
+        }
+    }
+}
+
+impl RelativePointer {
+    /// Return true if relative pointer refers to the key, or array index,
+    /// of a field, that is, it ends with ``#``.
+    pub fn is_key_name(&self) -> bool {
+        self.ptr.is_none()
+    }
+
+    /// Resolve relative pointer, against current location ``base``
+    /// within ``doc``, into an absolute pointer. For ``#`` form, return
+    /// the pointer to the field whose key, or index, is referred.
+    pub fn to_pointer(&self, doc: &Json, base: &Pointer) -> Result<Pointer> {
+        if self.up > base.len() {
+            err_at!(JptrFail, msg: "{} levels up from {:?}", self.up, base.to_string())?;
+        }
+        let mut loc = Pointer {
+            frags: base.frags[..base.len() - self.up].to_vec(),
+            strict: base.strict,
+        };
+        if let Some(shift) = self.shift {
+            let index = match loc.last() {
+                Some(key) if is_array_item(doc, &loc)? => key.parse::<isize>().ok(),
+                _ => None,
+            };
+            let index = match index {
+                Some(n) => n.checked_add(shift),
+                None => {
+                    err_at!(JptrFail, msg: "{:?} is not an array item", loc.to_string())?
+                }
+            };
+            match index {
+                Some(n) if n >= 0 => {
+                    loc.pop();
+                    loc.push(n.to_string());
+                }
+                Some(n) => err_at!(IndexOutofBound, msg: "{}", n)?,
+                None => err_at!(JptrFail, msg: "index shift {} overflows", shift)?,
+            }
+        }
+        Ok(match &self.ptr {
+            Some(ptr) => loc.join(ptr),
+            None => loc,
+        })
+    }
+
+    /// Resolve relative pointer against current location ``base`` within
+    /// ``doc``, and return the field. For ``#`` form, return the field's
+    /// key as string, or array index as integer.
+    pub fn resolve(&self, doc: &Json, base: &Pointer) -> Result<Json> {
+        let ptr = self.to_pointer(doc, base)?;
+        match (&self.ptr, ptr.last()) {
+            (Some(_), _) => doc.get(&ptr),
+            (None, Some(key)) => {
+                doc.pointer(&ptr)?;
+                match is_array_item(doc, &ptr)? {
+                    true => Ok(Json::new(err_at!(InvalidIndex, key.parse::<usize>())?)),
+                    false => Ok(Json::new(key)),
+                }
+            }
+            (None, None) => err_at!(JptrFail, msg: "root has no key name"),
+        }
+    }
+}
+
+fn is_array_item(doc: &Json, ptr: &Pointer) -> Result<bool> {
+    match ptr.parent() {
+        Some(parent) => Ok(matches!(doc.pointer(&parent)?, Json::Array(_))),
+        None => Ok(false),
+    }
+}
+
+// Parse non-negative integer, without leading zeros, from the start of
+// ``text``, and return the same along with remaining text.
+fn parse_number(text: &str) -> Result<(usize, &str)> {
+    let n = text.bytes().take_while(u8::is_ascii_digit).count();
+    match &text[..n] {
+        "" => err_at!(JptrFail, msg: "expected number in relative pointer"),
+        num if num.len() > 1 && num.starts_with('0') => {
+            err_at!(JptrFail, msg: "leading zero in {:?}", num)
+        }
+        num => Ok((err_at!(JptrFail, num.parse())?, &text[n..])),
+    }
+}
 
 /// quote path fragment using backslash escape and tilde escape defined by the
 /// RFC specification.
 ///
-/// After escaping each path-fragment caller can join them with '/'.
+/// After escaping each path-fragment caller can join them with '/', or
+/// use [Pointer] that does the same.
 pub fn quote(fragment: &str) -> String {
     let mut outs = String::new();
     for ch in fragment.chars() {
@@ -65,6 +435,26 @@ pub fn unquote(fragment: &str) -> Result<String> {
     Ok(outs)
 }
 
+/// Extract values at JSON ``pointers`` from JSON ``text``, in a single
+/// pass, without parsing the whole document. Values that are not on the
+/// path of any pointer are validated and skipped, without allocating.
+/// Return a result for each pointer, same as [Json::get] on the parsed
+/// document. If text fails to parse, all pointers fail with the same
+/// error.
+///
+/// ```
+/// use jsondata::{jptr, Json};
+///
+/// let text = r#"{"a": {"b": [1, 2]}, "c": [true, {"d": null}], "e": "x"}"#;
+/// let values = jptr::extract(text, &["/a/b", "/c/0", "/f"]);
+/// assert_eq!(values[0], Ok(Json::new(vec![1, 2])));
+/// assert_eq!(values[1], Ok(Json::new(true)));
+/// assert!(values[2].is_err());
+/// ```
+pub fn extract(text: &str, pointers: &[&str]) -> Vec<Result<Json>> {
+    Parser::new().extract(text, pointers)
+}
+
 pub(crate) fn fragments(path: &str) -> Result<(Vec<String>, String)> {
     let mut frags: Vec<String> = vec![];
     let mut frag = String::new();
@@ -86,7 +476,7 @@ pub(crate) fn fragments(path: &str) -> Result<(Vec<String>, String)> {
             ch if state.1 => err_at!(JptrFail, msg: "invalid ~{}", ch)?,
             '/' => {
                 frags.push(frag.clone());
-                frag.truncate(0);
+                frag.clear();
                 (state.0, state.1)
             }
             '\\' => (true, state.1),
@@ -100,26 +490,114 @@ pub(crate) fn fragments(path: &str) -> Result<(Vec<String>, String)> {
     Ok((frags, frag))
 }
 
-pub(crate) fn lookup_mut<'a>(
+// Return all fragments in path, including the last one.
+pub(crate) fn fragments_of(path: &str) -> Result<Vec<String>> {
+    if path.is_empty() {
+        Ok(vec![])
+    } else {
+        let (mut frags, key) = fragments(fix_prefix(path)?)?;
+        frags.push(key);
+        Ok(frags)
+    }
+}
+
+// Return the parent container of the field pointed by ``ptr``, along with
+// the field's key. Pointer must not be root.
+pub(crate) fn lookup_mut<'a, 'b>(
     mut json: &'a mut Json,
-    path: &str,
-) -> Result<(&'a mut Json, String)> {
-    let (frags, key) = fragments(path)?;
+    ptr: &'b Pointer,
+) -> Result<(&'a mut Json, &'b str)> {
+    let (key, frags) = match ptr.frags.split_last() {
+        Some(item) => item,
+        None => err_at!(JptrFail, msg: "root pointer has no parent")?,
+    };
     for frag in frags {
+        check_index(json, frag, ptr.strict)?;
         json = ops::index_mut(json, frag.as_str())?
     }
-    Ok((json, key))
+    check_index(json, key, ptr.strict)?;
+    Ok((json, key.as_str()))
 }
 
-pub(crate) fn lookup_ref<'a>(
+// Return the parent container of the field pointed by ``ptr``, along with
+// the field's key. Pointer must not be root.
+pub(crate) fn lookup_ref<'a, 'b>(
     mut json_doc: &'a Json,
-    path: &str,
-) -> Result<(&'a Json, String)> {
-    let (frags, key) = fragments(path)?;
+    ptr: &'b Pointer,
+) -> Result<(&'a Json, &'b str)> {
+    let (key, frags) = match ptr.frags.split_last() {
+        Some(item) => item,
+        None => err_at!(JptrFail, msg: "root pointer has no parent")?,
+    };
     for frag in frags {
+        check_index(json_doc, frag, ptr.strict)?;
         json_doc = json_doc[frag.as_str()].to_result()?;
     }
-    Ok((json_doc, key))
+    check_index(json_doc, key, ptr.strict)?;
+    Ok((json_doc, key.as_str()))
+}
+
+// In strict mode, array index must be ``-`` or a number without leading
+// zeros, refer RFC 6901 section 4.
+fn check_index(json: &Json, frag: &str, strict: bool) -> Result<()> {
+    let ok = match frag.as_bytes() {
+        _ if !strict || !matches!(json.resolve(), Json::Array(_)) => true,
+        b"-" | b"0" => true,
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    };
+    match ok {
+        true => Ok(()),
+        false => err_at!(InvalidIndex, msg: "{:?} in strict mode", frag),
+    }
+}
+
+// Return all fragments in path, unescaped strictly as per RFC 6901.
+fn rfc_fragments(path: &str) -> Result<Vec<String>> {
+    match path.strip_prefix('/') {
+        Some(path) => path.split('/').map(rfc_unescape).collect(),
+        None if path.is_empty() => Ok(vec![]),
+        None => err_at!(JptrFail, msg: "pointer should start with forward solidus"),
+    }
+}
+
+fn rfc_unescape(frag: &str) -> Result<String> {
+    let mut outs = String::new();
+    let mut chars = frag.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '~' => match chars.next() {
+                Some('0') => outs.push('~'),
+                Some('1') => outs.push('/'),
+                Some(ch) => err_at!(JptrFail, msg: "invalid ~{}", ch)?,
+                None => err_at!(JptrFail, msg: "invalid ~ at end of fragment")?,
+            },
+            ch => outs.push(ch),
+        }
+    }
+    Ok(outs)
+}
+
+fn percent_decode(text: &str) -> Result<String> {
+    let mut bytes = vec![];
+    let mut iter = text.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'%' => {
+                let hex = iter.next().zip(iter.next());
+                match hex.and_then(|(h, l)| Some((hexdigit(h)?, hexdigit(l)?))) {
+                    Some((h, l)) => bytes.push((h << 4) | l),
+                    None => err_at!(JptrFail, msg: "invalid percent encoding")?,
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+    err_at!(JptrFail, String::from_utf8(bytes))
+}
+
+fn hexdigit(b: u8) -> Option<u8> {
+    (b as char).to_digit(16).map(|d| d as u8)
 }
 
 pub(crate) fn fix_prefix(path: &str) -> Result<&str> {
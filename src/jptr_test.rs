@@ -1,7 +1,7 @@
 // Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
 
-use crate::jptr::{quote, unquote};
-use crate::{json::Json, property::Property};
+use crate::jptr::{extract, quote, unquote, Pointer};
+use crate::{json::Json, property::Property, Error};
 
 #[test]
 fn test_quote() {
@@ -216,3 +216,319 @@ fn test_jptr_delete() {
 
     assert_eq!(json, refv);
 }
+
+#[test]
+fn test_jptr_extract() {
+    use crate::Error;
+
+    let text = r#"{
+        "a": {"b": [1, {"c": "]}\"{["}], "~/": true},
+        "skip": [[[{"x": "A"}]], {"y": [1, 2, 3]}],
+        "n": 10,
+        "s": "str",
+        "arr": [0, [1, 2], {"k": null}]
+    }"#;
+    let pointers = [
+        "",
+        "/a",
+        "/a/b/1/c",
+        "/a/~0~1",
+        "/n",
+        "/s",
+        "/arr/1/0",
+        "/arr/-1/k",
+        "/arr/2",
+        "/skip/1/y/2",
+        "/missing",
+        "/arr/3",
+        "/arr/x",
+        "/n/0",
+        "/a/b/1/c/d",
+        "a",
+    ];
+    let json: Json = text.parse().unwrap();
+    let values = extract(text, &pointers);
+    assert_eq!(values.len(), pointers.len());
+    for (pointer, value) in pointers.iter().zip(values) {
+        assert_eq!(value, json.get(pointer), "{}", pointer);
+    }
+
+    // repeated keys, last occurrence wins.
+    let text = r#"{"a": {"b": 1}, "c": 2, "a": {"d": 3}}"#;
+    let values = extract(text, &["/a/b", "/a/d", "/c"]);
+    assert!(matches!(values[0], Err(Error::PropertyNotFound(_, _))));
+    assert_eq!(values[1], Ok(Json::new(3)));
+    assert_eq!(values[2], Ok(Json::new(2)));
+
+    // syntax errors on the path fail all pointers.
+    let values = extract(r#"{"a": 1, "b": [1,, 2]}"#, &["/a", "/b/0"]);
+    assert!(matches!(values[0], Err(Error::ParseFail(_, _, _))));
+    assert_eq!(values[0], values[1]);
+    let values = extract(r#"{"a": 1, "b": [1, 2}"#, &["/a"]);
+    assert!(matches!(values[0], Err(Error::ParseFail(_, _, _))));
+
+    // syntax errors in skipped values fail all pointers.
+    for text in [r#"{"a":1, "b": [nul, 1 2 }}]}"#, r#"{"a": 1, "b": {"c" 1}}"#] {
+        let values = extract(text, &["/a"]);
+        assert!(matches!(values[0], Err(Error::ParseFail(_, _, _))), "{}", text);
+    }
+}
+
+#[test]
+fn test_jptr_pointer() {
+    let ptr: Pointer = "".parse().unwrap();
+    assert!(ptr.is_empty());
+    assert_eq!(ptr, Pointer::root());
+    assert_eq!(ptr.parent(), None);
+    assert_eq!(ptr.to_string(), "");
+
+    let mut ptr: Pointer = r#"/a~1b/m~0n/i\j/0"#.parse().unwrap();
+    assert_eq!(ptr.len(), 4);
+    assert_eq!(ptr.as_fragments(), &["a/b", "m~n", r#"i\j"#, "0"]);
+    assert_eq!(ptr.last(), Some("0"));
+    assert_eq!(ptr.to_string(), r#"/a~1b/m~0n/i\j/0"#);
+    assert_eq!(Pointer::from_uri_fragment(&ptr.to_uri_fragment()).unwrap(), ptr);
+
+    assert_eq!(ptr.pop(), Some("0".to_string()));
+    ptr.push("");
+    assert_eq!(ptr.to_string(), r#"/a~1b/m~0n/i\j/"#);
+    let parent = ptr.parent().unwrap();
+    assert_eq!(parent.len(), 3);
+
+    let other: Pointer = vec!["x", "y/z"].into_iter().collect();
+    assert_eq!(parent.join(&other).to_string(), r#"/a~1b/m~0n/i\j/x/y~1z"#);
+    assert_eq!(Pointer::root().join(&other), other);
+
+    let ptr: Pointer = vec![r#"a"b\c"#, "d~e"].into_iter().collect();
+    assert_eq!(ptr.to_string(), r#"/a"b\c/d~0e"#);
+    let ptr: Pointer = vec!["a/b", "c"].into_iter().collect();
+    assert_eq!(ptr.to_string().parse::<Pointer>().unwrap(), ptr);
+
+    // keys with backslash round-trip, string paths honour backslash escapes.
+    let json: Json = r#"{"a\\b": 1, "ab": 2, "c\\": {"\\\\": 3}}"#.parse().unwrap();
+    for frags in [vec![r#"a\b"#], vec!["ab"], vec![r#"c\"#, r#"\\"#]] {
+        let ptr: Pointer = frags.into_iter().collect();
+        let parsed: Pointer = ptr.to_string().parse().unwrap();
+        assert_eq!(parsed, ptr);
+        assert_eq!(json.get(&parsed).unwrap(), json.get(&ptr).unwrap());
+    }
+    let ptr: Pointer = r#"/a\b"#.parse().unwrap();
+    assert_eq!(json.get(&ptr).unwrap(), Json::new(1));
+    assert_eq!(json.get(r#"/a\\b"#).unwrap(), Json::new(1));
+    assert_eq!(json.get(r#"/a\b"#).unwrap(), Json::new(2));
+
+    assert!("a/b".parse::<Pointer>().is_err());
+    assert!("/a~2".parse::<Pointer>().is_err());
+}
+
+#[test]
+fn test_jptr_pointer_crud() {
+    let mut json: Json = r#"{"a": {"b": [1, "x"]}, "c/d": 2}"#.parse().unwrap();
+    let ptrs: Vec<Pointer> = ["/a/b/0", "/a/b/1", "/c~1d", "/a/e"]
+        .iter()
+        .map(|p| p.parse().unwrap())
+        .collect();
+
+    assert_eq!(json.get(&ptrs[0]).unwrap(), Json::new(1));
+    assert_eq!(json.get(&ptrs[2]).unwrap(), Json::new(2));
+    assert_eq!(json.get(&Pointer::root()).unwrap(), json);
+    assert!(json.get(&ptrs[3]).is_err());
+
+    json.set(&ptrs[3], Json::new(true)).unwrap();
+    json.append(&ptrs[1], Json::new("y")).unwrap();
+    json.delete(&ptrs[2]).unwrap();
+    let refv: Json = r#"{"a": {"b": [1, "xy"], "e": true}}"#.parse().unwrap();
+    assert_eq!(json, refv);
+
+    let path = "/a/e".to_string();
+    assert_eq!(json.get(&path).unwrap(), Json::new(true));
+    assert!(json.get("a/e").is_err());
+}
+
+#[test]
+fn test_jptr_pointer_ref() {
+    use crate::parse::Parser;
+
+    let text = r#"{"a": {"b": [1, {"c": "x"}]}, "m~n": [true]}"#;
+    let mut json: Json = text.parse().unwrap();
+
+    assert_eq!(json.pointer("").unwrap(), &json.clone());
+    assert_eq!(json.pointer("/a/b/1/c").unwrap().as_str(), Some("x"));
+    assert_eq!(json.pointer("/m~0n/0").unwrap(), &Json::new(true));
+    assert!(json.pointer("/a/z").is_err());
+    assert!(json.pointer("/a/b/5/c").is_err());
+    assert!(json.pointer("a").is_err());
+
+    *json.pointer_mut("/a/b/1/c").unwrap() = Json::new(10);
+    if let Json::Array(arr) = json.pointer_mut("/m~0n").unwrap() {
+        arr.push(Json::Null);
+    }
+    assert!(json.pointer_mut("/a/b/x").is_err());
+    assert!(json.pointer_mut("/m~0n/2").is_err());
+    let refv: Json =
+        r#"{"a": {"b": [1, {"c": 10}]}, "m~n": [true, null]}"#.parse().unwrap();
+    assert_eq!(json, refv);
+
+    let mut json = Parser::new().lazy_depth(1).parse(text).unwrap();
+    *json.pointer_mut("/a/b/0").unwrap() = Json::new(2);
+    assert_eq!(json.pointer("/a/b/0").unwrap(), &Json::new(2));
+    assert_eq!(json.pointer("/a/b/1/c").unwrap().as_str(), Some("x"));
+}
+
+#[test]
+fn test_jptr_dash_index() {
+    let mut json: Json = r#"{"a": [1, 2], "s": "x"}"#.parse().unwrap();
+    json.set("/a/-", Json::new(3)).unwrap();
+    json.append("/a/-", Json::new(4)).unwrap();
+    json.append("/s", Json::new("y")).unwrap();
+    assert_eq!(json.get("/a").unwrap(), Json::new(vec![1, 2, 3, 4]));
+    assert_eq!(json.get("/s").unwrap(), Json::new("xy"));
+
+    assert!(json.get("/a/-").is_err());
+    assert!(json.delete("/a/-").is_err());
+    assert!(json.set("/a/-/b", Json::Null).is_err());
+    json.set("/-", Json::Null).unwrap();
+    assert_eq!(json.get("/-").unwrap(), Json::Null);
+}
+
+#[test]
+fn test_jptr_strict() {
+    let mut json: Json = r#"{"a": [1, 2, [3]], "01": {"-1": 4}}"#.parse().unwrap();
+    let ptr = |path: &str| path.parse::<Pointer>().unwrap().strict(true);
+
+    assert!(!"/a".parse::<Pointer>().unwrap().is_strict());
+    assert!(ptr("/a").is_strict());
+    assert!(ptr("/a/0").parent().unwrap().is_strict());
+    assert_eq!(json.get("/a/-1").unwrap(), Json::new(vec![3]));
+    assert_eq!(json.get(&ptr("/a/2/0")).unwrap(), Json::new(3));
+    assert_eq!(json.get(&ptr("/01/-1")).unwrap(), Json::new(4));
+    for path in ["/a/-1", "/a/01", "/a/-1/0", "/a/+1", "/a/ 1", "/a/"].iter() {
+        match json.get(&ptr(path)) {
+            Err(crate::Error::InvalidIndex(_, _)) => (),
+            res => panic!("{} {:?}", path, res),
+        }
+    }
+    assert!(json.pointer(&ptr("/a/00")).is_err());
+    assert!(json.append(&ptr("/a/-01"), Json::new(5)).is_err());
+    assert!(json.delete(&ptr("/a/01")).is_err());
+    json.set(&ptr("/a/-"), Json::new(5)).unwrap();
+    json.delete(&ptr("/a/10")).unwrap_err();
+    json.delete(&ptr("/a/1")).unwrap();
+    assert_eq!(json.get("/a").unwrap(), r#"[1, [3], 5]"#.parse().unwrap());
+}
+
+#[test]
+fn test_jptr_uri_fragment() {
+    let text = r#"
+       {
+          "foo": ["bar", "baz"],
+          "": 0,
+          "a/b": 1,
+          "c%d": 2,
+          "e^f": 3,
+          "g|h": 4,
+          "i\\j": 5,
+          "k\"l": 6,
+          " ": 7,
+          "m~n": 8,
+          "é": 9
+       }
+    "#;
+    let json: Json = text.parse().unwrap();
+    let testcases = [
+        ("#", json.clone()),
+        ("#/foo", Json::new(vec!["bar", "baz"])),
+        ("#/foo/0", Json::new("bar")),
+        ("#/", Json::new(0)),
+        ("#/a~1b", Json::new(1)),
+        ("#/c%25d", Json::new(2)),
+        ("#/e%5Ef", Json::new(3)),
+        ("#/g%7Ch", Json::new(4)),
+        ("#/i%5Cj", Json::new(5)),
+        ("#/k%22l", Json::new(6)),
+        ("#/%20", Json::new(7)),
+        ("#/m~0n", Json::new(8)),
+        ("#/%C3%A9", Json::new(9)),
+    ];
+    for (frag, refv) in testcases.iter() {
+        let ptr = Pointer::from_uri_fragment(frag).unwrap();
+        assert_eq!(ptr.to_uri_fragment(), *frag);
+        assert_eq!(&json.get(&ptr).unwrap(), refv, "{}", frag);
+        assert_eq!(&json.get(*frag).unwrap(), refv, "{}", frag);
+    }
+    assert_eq!(Pointer::from_uri_fragment("#/c%25d").unwrap().to_string(), "/c%d");
+    assert_eq!(Pointer::from_uri_fragment("#/k%22l").unwrap().last(), Some("k\"l"));
+
+    for frag in ["/a", "#a", "#/%2", "#/%zz", "#/%FF", "#/~2", "#/a~"].iter() {
+        assert!(Pointer::from_uri_fragment(frag).is_err(), "{}", frag);
+    }
+}
+
+#[test]
+fn test_jptr_relative() {
+    use crate::jptr::RelativePointer;
+
+    let text =
+        r#"{"foo": ["bar", "baz", "biz"], "highly": {"nested": {"objects": true}}}"#;
+    let json: Json = text.parse().unwrap();
+
+    let base: Pointer = "/foo/1".parse().unwrap();
+    let testcases = [
+        ("0", Json::new("baz")),
+        ("1/0", Json::new("bar")),
+        ("0-1", Json::new("bar")),
+        ("0+1", Json::new("biz")),
+        ("2/highly/nested/objects", Json::new(true)),
+        ("0#", Json::new(1)),
+        ("0+1#", Json::new(2)),
+        ("1#", Json::new("foo")),
+    ];
+    for (rel, refv) in testcases.iter() {
+        let rel: RelativePointer = rel.parse().unwrap();
+        assert_eq!(rel.resolve(&json, &base).unwrap(), *refv, "{}", rel);
+    }
+
+    let base: Pointer = "/highly/nested".parse().unwrap();
+    let testcases = [
+        ("0/objects", Json::new(true)),
+        ("1/nested/objects", Json::new(true)),
+        ("2/foo/0", Json::new("bar")),
+        ("0#", Json::new("nested")),
+        ("1#", Json::new("highly")),
+        ("2", json.clone()),
+    ];
+    for (rel, refv) in testcases.iter() {
+        let rel: RelativePointer = rel.parse().unwrap();
+        assert_eq!(rel.resolve(&json, &base).unwrap(), *refv, "{}", rel);
+    }
+
+    let rel: RelativePointer = "1-1/a~1b".parse().unwrap();
+    assert_eq!(rel.to_string(), "1-1/a~1b");
+    assert!(!rel.is_key_name());
+    let base: Pointer = "/foo/1/x".parse().unwrap();
+    assert_eq!(rel.to_pointer(&json, &base).unwrap().to_string(), "/foo/0/a~1b");
+    assert_eq!("0+2#".parse::<RelativePointer>().unwrap().to_string(), "0+2#");
+    assert!("0#".parse::<RelativePointer>().unwrap().is_key_name());
+
+    let base: Pointer = "/foo/1".parse().unwrap();
+    for rel in ["3", "0-2", "1+1", "2#", "0+2/x"].iter() {
+        let rel: RelativePointer = rel.parse().unwrap();
+        assert!(rel.resolve(&json, &base).is_err(), "{}", rel);
+    }
+    for text in ["", "#", "01", "0+", "0-01", "1x", "0/a~2", "-1"].iter() {
+        assert!(text.parse::<RelativePointer>().is_err(), "{}", text);
+    }
+
+    let rel: RelativePointer = format!("0+{}", isize::MAX).parse().unwrap();
+    match rel.to_pointer(&json, &base) {
+        Err(Error::JptrFail(_, _)) => (),
+        res => panic!("{:?}", res),
+    }
+    let n = (isize::MAX as usize) + 1;
+    for text in [format!("0+{}", n), format!("0-{}", n)].iter() {
+        match text.parse::<RelativePointer>() {
+            Err(Error::JptrFail(_, _)) => (),
+            res => panic!("{} {:?}", text, res),
+        }
+    }
+}
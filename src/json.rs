@@ -3,10 +3,12 @@
 use std::cmp::{Ord, Ordering, PartialOrd};
 use std::convert::{From, TryFrom, TryInto};
 use std::fmt::{self, Display, Write};
-use std::{default::Default, ops::RangeBounds, str::FromStr};
+use std::{default::Default, io, ops::RangeBounds, str::FromStr};
 
+use crate::jptr::{self, AsPointer};
 use crate::num::{Floating, Integral};
-use crate::{jptr, lex::Lex, ops, parse::parse_value, property::Property, Error, Result};
+use crate::{canonical, format::Format, ops, parse::Parser};
+use crate::{lazy::Lazy, property::Property, writer, Error, Result};
 
 // TODO: test case for all combination for JsonSerialize,
 // refer to examples/macro.rs
@@ -79,6 +81,15 @@ use crate::{jptr, lex::Lex, ops, parse::parse_value, property::Property, Error,
 /// - Json types are clone-able but do not implement [Copy].
 /// - [Json] value can be serialized into JSON format using [Display] trait.
 ///
+/// **Lazy values**
+///
+/// Arrays and objects captured by [Parser::lazy_depth] and
+/// [Parser::lazy_pointer] are held as unparsed text, and do not match the
+/// Array and Object variants, until they are materialized. Accessor and
+/// conversion methods, JSON Pointer lookups, comparison and serialization
+/// materialize them transparently. Values parsed with such options shall
+/// be materialized with [Json::compute] before matching on the variants.
+///
 /// **Panics**
 ///
 /// [Json] implements AsRef and AsMut traits for [str], Vec<[Json]>,
@@ -87,8 +98,9 @@ use crate::{jptr, lex::Lex, ops, parse::parse_value, property::Property, Error,
 ///
 /// [string]: std::string::String
 /// [total ordering]: https://en.wikipedia.org/wiki/Total_order
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Default)]
 pub enum Json {
+    #[default]
     Null,
     Bool(bool),
     Integer(Integral),
@@ -100,6 +112,8 @@ pub enum Json {
     #[doc(hidden)]
     __Error(Error),
     #[doc(hidden)]
+    __Lazy(Box<Lazy>),
+    #[doc(hidden)]
     __Minbound,
     #[doc(hidden)]
     __Maxbound,
@@ -144,6 +158,33 @@ impl Json {
         Json::from(value)
     }
 
+    /// Parse UTF-8 encoded JSON text into [Json], without validating it
+    /// as [str] first. Same as [Parser::parse_slice] with default
+    /// configuration.
+    ///
+    /// ```
+    /// let json = jsondata::Json::from_slice(br#"{"a": [1, 2]}"#).unwrap();
+    /// assert_eq!(json.get("/a/1").unwrap(), jsondata::Json::new(2));
+    /// ```
+    pub fn from_slice(text: &[u8]) -> Result<Json> {
+        Parser::new().parse_slice(text)
+    }
+
+    /// Read UTF-8 encoded JSON text from ``reader`` and parse it into
+    /// [Json]. Same as [Parser::parse_reader] with default configuration.
+    ///
+    /// ```
+    /// let text = br#"{"a": [1, 2]}"#;
+    /// let json = jsondata::Json::from_reader(text.as_ref()).unwrap();
+    /// assert_eq!(json.get("/a/0").unwrap(), jsondata::Json::new(1));
+    /// ```
+    pub fn from_reader<R>(reader: R) -> Result<Json>
+    where
+        R: io::Read,
+    {
+        Parser::new().parse_reader(reader)
+    }
+
     /// Minbound return a Json value that sort before every other [Json] type.
     #[allow(dead_code)]
     pub(crate) fn minbound() -> Json {
@@ -163,6 +204,10 @@ impl Json {
         use crate::json::Json::{Array, Float, Integer, Object};
 
         match self {
+            Json::__Lazy(_) => {
+                self.resolve_mut().to_result()?;
+                self.validate()?
+            }
             Array(items) => {
                 for item in items.iter_mut() {
                     item.validate()?
@@ -201,6 +246,10 @@ impl Json {
         use crate::json::Json::{Array, Float, Integer, Object};
 
         match self {
+            Json::__Lazy(_) => {
+                self.resolve_mut().to_result()?;
+                self.compute()?
+            }
             Array(items) => {
                 for item in items.iter_mut() {
                     item.compute()?
@@ -232,6 +281,7 @@ impl Json {
             Json::Array(_) => "array".to_string(),
             Json::Object(_) => "object".to_string(),
             Json::__Error(_) => "error".to_string(),
+            Json::__Lazy(lazy) => lazy.as_value().type_name(),
             Json::__Minbound => "minbound".to_string(),
             Json::__Maxbound => "maxbound".to_string(),
         }
@@ -241,33 +291,95 @@ impl Json {
 /// Implementation provides CRUD access into [Json] document using
 /// [Json Pointer]. For all methods,
 ///
-/// * Path must be valid JSON Pointer.
+/// * Path must be valid JSON Pointer, either a string or a parsed
+///   [jptr::Pointer].
 /// * Path fragment must be valid key if parent container is an object.
 /// * Path fragment must be a number index if parent container is an array.
 ///
 /// [JSON Pointer]: https://tools.ietf.org/html/rfc6901
 impl Json {
     /// Get a json field, within the document, locatable by ``path``.
-    pub fn get(&self, path: &str) -> Result<Json> {
-        if path.is_empty() {
+    pub fn get<P>(&self, path: &P) -> Result<Json>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        if ptr.is_empty() {
             Ok(self.clone())
         } else {
-            let path = jptr::fix_prefix(path)?;
-            let (json, key) = jptr::lookup_ref(self, path)?;
-            Ok(json[key.as_str()].to_result()?.clone())
+            let (json, key) = jptr::lookup_ref(self, &ptr)?;
+            Ok(json[key].to_result()?.clone())
+        }
+    }
+
+    /// Get a reference to the json field, within the document, locatable
+    /// by ``path``. Unlike [Json::get], the field is not cloned.
+    ///
+    /// ```
+    /// use jsondata::Json;
+    ///
+    /// let json: Json = r#"{"a": {"b": ["x", "y"]}}"#.parse().unwrap();
+    /// assert_eq!(json.pointer("/a/b/1").unwrap().as_str(), Some("y"));
+    /// ```
+    pub fn pointer<P>(&self, path: &P) -> Result<&Json>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        if ptr.is_empty() {
+            self.to_result()
+        } else {
+            let (json, key) = jptr::lookup_ref(self, &ptr)?;
+            json[key].to_result()
+        }
+    }
+
+    /// Get a mutable reference to the json field, within the document,
+    /// locatable by ``path``, to update the field in place.
+    ///
+    /// ```
+    /// use jsondata::Json;
+    ///
+    /// let mut json: Json = r#"{"a": {"b": [1, 2]}}"#.parse().unwrap();
+    /// *json.pointer_mut("/a/b/0").unwrap() = Json::new("one");
+    /// assert_eq!(json.get("/a/b").unwrap(), Json::new(vec![Json::new("one"), Json::new(2)]));
+    /// ```
+    pub fn pointer_mut<P>(&mut self, path: &P) -> Result<&mut Json>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        let json = if ptr.is_empty() {
+            self.resolve_mut()
+        } else {
+            let (json, key) = jptr::lookup_mut(self, &ptr)?;
+            ops::index_mut(json, key)?.resolve_mut()
+        };
+        match json {
+            Json::__Error(err) => Err(err.clone()),
+            json => Ok(json),
         }
     }
 
     /// Set a json field, within the document, locatable by ``path``.
-    pub fn set(&mut self, path: &str, value: Json) -> Result<()> {
-        if path.is_empty() {
+    /// If ``path`` ends with array index ``-``, value is added to the end
+    /// of array.
+    pub fn set<P>(&mut self, path: &P, value: Json) -> Result<()>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        if ptr.is_empty() {
             return Ok(());
         }
 
-        let path = jptr::fix_prefix(path)?;
-
-        let (json, frag) = jptr::lookup_mut(self, path)?;
+        let (json, frag) = jptr::lookup_mut(self, &ptr)?;
+        let json = json.resolve_mut();
         match json {
+            Json::Array(arr) if frag == "-" => {
+                arr.push(value);
+                Ok(())
+            }
             Json::Array(arr) => match frag.parse::<usize>() {
                 Ok(n) => {
                     if n >= arr.len() {
@@ -280,13 +392,13 @@ impl Json {
                 Err(err) => err_at!(InvalidIndex, msg: "{}", err),
             },
             Json::Object(props) => {
-                match props.binary_search_by(|p| p.as_key().cmp(&frag)) {
+                match props.binary_search_by(|p| p.as_key().cmp(frag)) {
                     Ok(n) => {
                         props[n].set_value(value);
                         Ok(())
                     }
                     Err(n) => {
-                        props.insert(n, Property::new(frag, value));
+                        props.insert(n, Property::new(frag.to_string(), value));
                         Ok(())
                     }
                 }
@@ -296,14 +408,17 @@ impl Json {
     }
 
     /// Delete a JSON field, within the document, locatable by ``path``.
-    pub fn delete(&mut self, path: &str) -> Result<()> {
-        if path.is_empty() {
+    pub fn delete<P>(&mut self, path: &P) -> Result<()>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        if ptr.is_empty() {
             return Ok(());
         }
 
-        let path = jptr::fix_prefix(path)?;
-
-        let (json, frag) = jptr::lookup_mut(self, path)?;
+        let (json, frag) = jptr::lookup_mut(self, &ptr)?;
+        let json = json.resolve_mut();
         match json {
             Json::Array(arr) => match frag.parse::<usize>() {
                 Ok(n) => {
@@ -317,7 +432,7 @@ impl Json {
                 Err(err) => err_at!(InvalidIndex, msg: "{}", err),
             },
             Json::Object(props) => {
-                match props.binary_search_by(|p| p.as_key().cmp(&frag)) {
+                match props.binary_search_by(|p| p.as_key().cmp(frag)) {
                     Ok(n) => {
                         props.remove(n);
                         Ok(())
@@ -330,15 +445,22 @@ impl Json {
     }
 
     /// Append a string or array to a JSON field within the document that is
-    /// either a string or array.
-    pub fn append(&mut self, path: &str, value: Json) -> Result<()> {
-        if path.is_empty() {
+    /// either a string or array. If ``path`` ends with array index ``-``,
+    /// value is added to the end of array.
+    pub fn append<P>(&mut self, path: &P, value: Json) -> Result<()>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let ptr = path.as_pointer()?;
+        if ptr.is_empty() {
             return Ok(());
         }
-        let path = jptr::fix_prefix(path)?;
-
-        let (json, frag) = jptr::lookup_mut(self, path)?;
-        match ops::index_mut(json, frag.as_str())? {
+        let (json, frag) = jptr::lookup_mut(self, &ptr)?;
+        if let (Json::Array(arr), "-") = (json.resolve_mut(), frag) {
+            arr.push(value);
+            return Ok(());
+        }
+        match ops::index_mut(json, frag)?.resolve_mut() {
             Json::String(j) => {
                 if let Json::String(s) = value {
                     j.push_str(&s);
@@ -372,8 +494,8 @@ impl Json {
     {
         use std::ops::Bound::{Excluded, Included, Unbounded};
 
-        match self {
-            Json::__Error(_) => self.clone(),
+        match self.resolve() {
+            err @ Json::__Error(_) => err.clone(),
             Json::Array(arr) => {
                 let (start, s) = match range.start_bound() {
                     Included(n) => (ops::normalized_offset(*n, arr.len()), *n),
@@ -409,25 +531,25 @@ impl Json {
 /// follows a schema-less data representation.
 impl Json {
     pub fn is_null(&self) -> bool {
-        matches!(self, Json::Null)
+        matches!(self.resolve(), Json::Null)
     }
 
     pub fn to_bool(&self) -> Option<bool> {
-        match self {
+        match self.resolve() {
             Json::Bool(s) => Some(*s),
             _ => None,
         }
     }
 
     pub fn to_integer(&self) -> Option<i128> {
-        match self {
+        match self.resolve() {
             Json::Integer(item) => item.integer(),
             _ => None,
         }
     }
 
     pub fn to_float(&self) -> Option<f64> {
-        match self {
+        match self.resolve() {
             Json::Float(item) => item.float(),
             Json::Integer(item) => item.float(),
             _ => None,
@@ -435,45 +557,135 @@ impl Json {
     }
 
     pub fn as_str(&self) -> Option<&str> {
-        match self {
+        match self.resolve() {
             Json::String(s) => Some(s),
             _ => None,
         }
     }
 
     pub fn to_array(&self) -> Option<Vec<Json>> {
-        match self {
+        match self.resolve() {
             Json::Array(arr) => Some(arr.clone()),
             _ => None,
         }
     }
 
     pub fn to_object(&self) -> Option<Vec<Property>> {
-        match self {
+        match self.resolve() {
             Json::Object(obj) => Some(obj.clone()),
             _ => None,
         }
     }
 
     pub fn is_error(&self) -> bool {
-        matches!(self, Json::__Error(_))
+        matches!(self.resolve(), Json::__Error(_))
     }
 
     pub fn to_error(&self) -> Option<Error> {
-        match self {
+        match self.resolve() {
             Json::__Error(err) => Some(err.clone()),
             _ => None,
         }
     }
 
     pub fn to_result(&self) -> Result<&Json> {
-        match self {
+        match self.resolve() {
             Json::__Error(err) => Err(err.clone()),
-            _ => Ok(self),
+            json => Ok(json),
         }
     }
 }
 
+/// Implementation resolves arrays and objects that are captured by
+/// [Parser::lazy_depth] or [Parser::lazy_pointer], parsing them on first
+/// access.
+impl Json {
+    pub(crate) fn is_lazy(&self) -> bool {
+        matches!(self, Json::__Lazy(_))
+    }
+
+    pub(crate) fn resolve(&self) -> &Json {
+        match self {
+            Json::__Lazy(lazy) => lazy.as_value(),
+            json => json,
+        }
+    }
+
+    pub(crate) fn resolve_mut(&mut self) -> &mut Json {
+        if self.is_lazy() {
+            *self = std::mem::take(self).into_resolved();
+        }
+        self
+    }
+
+    pub(crate) fn into_resolved(self) -> Json {
+        match self {
+            Json::__Lazy(lazy) => lazy.into_value(),
+            json => json,
+        }
+    }
+}
+
+/// Implementation provides methods to serialize [Json] into JSON text,
+/// other than the compact form generated by the [Display] trait.
+impl Json {
+    /// Serialize into human readable JSON text, using default [Format].
+    ///
+    /// ```
+    /// let json: jsondata::Json = r#"{"a": [1, 2]}"#.parse().unwrap();
+    /// assert_eq!(json.to_pretty_string(), "{\n  \"a\": [\n    1,\n    2\n  ]\n}");
+    /// ```
+    pub fn to_pretty_string(&self) -> String {
+        // default format, never fails.
+        self.to_string_with(&Format::new()).unwrap()
+    }
+
+    /// Serialize into JSON text, using the supplied [Format]. Fails only
+    /// when the format is configured to reject values that cannot be
+    /// represented in the requested [crate::Mode].
+    pub fn to_string_with(&self, format: &Format) -> Result<String> {
+        let mut s = String::new();
+        format.write(&mut s, self)?;
+        Ok(s)
+    }
+
+    /// Serialize into canonical JSON text as per
+    /// [RFC-8785](https://tools.ietf.org/html/rfc8785), that is, two
+    /// semantically equal documents shall always serialize into
+    /// identical bytes. Useful for hashing and signing documents.
+    ///
+    /// * Object properties are sorted by UTF-16 code units of their key.
+    /// * Numbers are serialized as IEEE-754 double precision values,
+    ///   integers beyond 53-bit precision shall lose their precision.
+    /// * Strings are serialized with minimal escaping.
+    ///
+    /// Returns [Error::InvalidNumber] for NaN and Infinity values.
+    ///
+    /// ```
+    /// let json: jsondata::Json = r#"{"b": [1.50, 1E30], "a": "\u0041"}"#.parse().unwrap();
+    /// let text = json.to_canonical_string().unwrap();
+    /// assert_eq!(text, r#"{"a":"A","b":[1.5,1e+30]}"#);
+    /// ```
+    pub fn to_canonical_string(&self) -> Result<String> {
+        let mut s = String::new();
+        canonical::write(&mut s, self)?;
+        Ok(s)
+    }
+
+    /// Serialize into compact JSON text, same as the [Display] trait,
+    /// writing it directly into ``w``. Useful for large documents,
+    /// where materializing the entire text in memory is not desirable.
+    ///
+    /// Use [crate::Writer] to serialize a stream of values, with
+    /// buffering and optional pretty printing.
+    pub fn write_to<W>(&self, w: &mut W) -> Result<()>
+    where
+        W: io::Write,
+    {
+        writer::write_compact(w, self)
+    }
+}
+
 impl Json {
     pub(crate) fn to_integer_result(&self) -> Result<i128> {
         match self {
@@ -495,9 +707,9 @@ impl Eq for Json {}
 impl PartialEq for Json {
     fn eq(&self, other: &Json) -> bool {
         use crate::Json::{Array, Bool, Float, Integer, Null, Object, String as S};
-        use std::i128;
 
-        match (self, other) {
+        let (this, other) = (self.resolve(), other.resolve());
+        match (this, other) {
             (Null, Null) => true,
             (Bool(a), Bool(b)) => a == b,
             (Integer(_), Integer(_)) => self.to_integer() == other.to_integer(),
@@ -554,7 +766,8 @@ impl Ord for Json {
     fn cmp(&self, other: &Json) -> Ordering {
         use crate::Json::{Array, Bool, Float, Integer, Null, Object, String as S};
 
-        match (self, other) {
+        let (a, b) = (self.resolve(), other.resolve());
+        match (a, b) {
             // typically we assume that value at same position is same type.
             (Null, Null) => Ordering::Equal,
             (Bool(a), Bool(b)) => {
@@ -666,16 +879,14 @@ impl Ord for Json {
             (_, S(_)) => Ordering::Greater,
             (Array(_), _) => Ordering::Less,
             (_, Array(_)) => Ordering::Greater,
+            // resolve() never returns a lazy value, these arms only keep
+            // the match exhaustive.
+            (Json::__Lazy(lazy), _) => lazy.as_value().cmp(b),
+            (_, Json::__Lazy(lazy)) => a.cmp(lazy.as_value()),
         }
     }
 }
 
-impl Default for Json {
-    fn default() -> Json {
-        Json::Null
-    }
-}
-
 macro_rules! convert_nums {
     (bool, $var:ident, $method:ident) => {
         impl From<bool> for Json {
@@ -917,6 +1128,7 @@ impl From<Json> for bool {
             Array(a) => !a.is_empty(),
             Object(o) => !o.is_empty(),
             Json::__Error(_) => false,
+            Json::__Lazy(lazy) => bool::from(lazy.into_value()),
             Json::__Minbound => true,
             Json::__Maxbound => true,
         }
@@ -927,14 +1139,13 @@ impl FromStr for Json {
     type Err = Error;
 
     fn from_str(text: &str) -> Result<Json> {
-        let mut lex = Lex::new(0, 1, 1);
-        parse_value(text, &mut lex)
+        Parser::new().parse(text)
     }
 }
 
 impl AsRef<str> for Json {
     fn as_ref(&self) -> &str {
-        match self {
+        match self.resolve() {
             Json::String(s) => s,
             _ => panic!("Json is not string"),
         }
@@ -943,7 +1154,7 @@ impl AsRef<str> for Json {
 
 impl AsRef<Vec<Json>> for Json {
     fn as_ref(&self) -> &Vec<Json> {
-        match self {
+        match self.resolve() {
             Json::Array(arr) => arr,
             _ => panic!("Json is not an array"),
         }
@@ -952,7 +1163,7 @@ impl AsRef<Vec<Json>> for Json {
 
 impl AsRef<Vec<Property>> for Json {
     fn as_ref(&self) -> &Vec<Property> {
-        match self {
+        match self.resolve() {
             Json::Object(obj) => obj,
             _ => panic!("Json is not an object"),
         }
@@ -961,7 +1172,7 @@ impl AsRef<Vec<Property>> for Json {
 
 impl AsMut<str> for Json {
     fn as_mut(&mut self) -> &mut str {
-        match self {
+        match self.resolve_mut() {
             Json::String(s) => s,
             _ => panic!("Json is not string"),
         }
@@ -970,7 +1181,7 @@ impl AsMut<str> for Json {
 
 impl AsMut<Vec<Json>> for Json {
     fn as_mut(&mut self) -> &mut Vec<Json> {
-        match self {
+        match self.resolve_mut() {
             Json::Array(arr) => arr,
             _ => panic!("Json is not an array"),
         }
@@ -979,7 +1190,7 @@ impl AsMut<Vec<Json>> for Json {
 
 impl AsMut<Vec<Property>> for Json {
     fn as_mut(&mut self) -> &mut Vec<Property> {
-        match self {
+        match self.resolve_mut() {
             Json::Object(obj) => obj,
             _ => panic!("Json is not an object"),
         }
@@ -1012,7 +1223,7 @@ impl Display for Json {
                 } else {
                     write!(f, "{:e}", v)
                 }
-            },
+            }
             S(val) => {
                 encode_string(f, val)?;
                 Ok(())
@@ -1046,18 +1257,29 @@ impl Display for Json {
                 }
             }
             Json::__Error(err) => write!(f, "error: {:?}", err),
+            Json::__Lazy(lazy) => write!(f, "{}", lazy.as_value()),
             Json::__Minbound => write!(f, "minbound"),
             Json::__Maxbound => write!(f, "maxbound"),
         }
     }
 }
 
-fn encode_string<W: Write>(w: &mut W, val: &str) -> fmt::Result {
-    write!(w, "\"")?;
+pub(crate) fn encode_string<W: Write>(w: &mut W, val: &str) -> fmt::Result {
+    encode_quoted(w, val, '"')
+}
+
+// Same as encode_string, but quote the string with either double-quote
+// or single-quote, where the other quote character is not escaped.
+pub(crate) fn encode_quoted<W: Write>(w: &mut W, val: &str, quote: char) -> fmt::Result {
+    write!(w, "{}", quote)?;
 
     let mut start = 0;
     for (i, byte) in val.bytes().enumerate() {
-        let escstr = ESCAPE[usize::from(byte)];
+        let escstr = match byte {
+            b'"' if quote == '\'' => "",
+            b'\'' if quote == '\'' => "\\'",
+            _ => ESCAPE[usize::from(byte)],
+        };
         if escstr.is_empty() {
             continue;
         }
@@ -1071,7 +1293,7 @@ fn encode_string<W: Write>(w: &mut W, val: &str) -> fmt::Result {
     if start != val.len() {
         write!(w, "{}", &val[start..])?;
     }
-    write!(w, "\"")
+    write!(w, "{}", quote)
 }
 
 pub fn insert(json: &mut Json, item: Property) {
@@ -0,0 +1,289 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::{borrow::Cow, ops::Index};
+
+use lazy_static::lazy_static;
+
+use crate::num::{Floating, Integral};
+use crate::parse::{Node, Parser};
+use crate::{
+    error::Location, jptr::AsPointer, json::Json, ops, property::Property, Error, Result,
+};
+
+/// JsonRef type is a borrowed representation of JSON document, parsed
+/// using [Parser::parse_ref] or [JsonRef::parse].
+///
+/// * Strings and property keys are sliced out of the parsed text, only
+///   strings and keys with escape sequences are decoded into an owned
+///   [String].
+/// * Numbers are kept as lexemes, and converted on access using the
+///   same deferred conversion as [Json] numbers.
+/// * Arrays are implemented as vector of JsonRef values, and objects as
+///   vector of [PropertyRef], sorted by key.
+///
+/// Values are read using the same JSON Pointer and index API as [Json],
+/// and can be converted to [Json] using [JsonRef::to_owned]. Equality
+/// compares number lexemes, hence `1.0` is not equal to `1.00`.
+///
+/// ```
+/// use jsondata::{Json, JsonRef};
+///
+/// let text = r#"{"users": [{"name": "Joe"}, {"name": "Ann"}]}"#;
+/// let value = JsonRef::parse(text).unwrap();
+/// assert_eq!(value["users"][-1]["name"].as_str(), Some("Ann"));
+/// assert_eq!(value.to_owned(), text.parse::<Json>().unwrap());
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum JsonRef<'a> {
+    Null,
+    Bool(bool),
+    Integer(&'a str),
+    Float(&'a str),
+    String(Cow<'a, str>),
+    Array(Vec<JsonRef<'a>>),
+    Object(Vec<PropertyRef<'a>>),
+    // Hidden variants
+    #[doc(hidden)]
+    __Error(Error),
+}
+
+/// PropertyRef type captures a single (key,value) pair in a [JsonRef]
+/// object, where key is borrowed from the parsed text unless it has
+/// escape sequences.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PropertyRef<'a>(Cow<'a, str>, JsonRef<'a>);
+
+impl<'a> PropertyRef<'a> {
+    #[inline]
+    pub fn as_key(&self) -> &str {
+        &self.0
+    }
+
+    #[inline]
+    pub fn as_value(&self) -> &JsonRef<'a> {
+        &self.1
+    }
+}
+
+impl<'a> JsonRef<'a> {
+    /// Parse ``text`` with default [Parser] configuration.
+    pub fn parse(text: &'a str) -> Result<JsonRef<'a>> {
+        Parser::new().parse_ref(text)
+    }
+
+    /// Convert to owned [Json] value, copying strings, keys and number
+    /// lexemes.
+    pub fn to_owned(&self) -> Json {
+        match self {
+            JsonRef::Null => Json::Null,
+            JsonRef::Bool(value) => Json::Bool(*value),
+            JsonRef::Integer(lexeme) => match Integral::try_from(*lexeme) {
+                Ok(value) => Json::Integer(value),
+                Err(err) => Json::__Error(err),
+            },
+            JsonRef::Float(lexeme) if is_non_finite(lexeme) => {
+                match err_at!(InvalidNumber, lexeme.parse::<f64>()) {
+                    Ok(value) => Json::new(value),
+                    Err(err) => Json::__Error(err),
+                }
+            }
+            JsonRef::Float(lexeme) => match Floating::try_from(*lexeme) {
+                Ok(value) => Json::Float(value),
+                Err(err) => Json::__Error(err),
+            },
+            JsonRef::String(value) => Json::String(value.to_string()),
+            JsonRef::Array(items) => {
+                Json::Array(items.iter().map(|v| v.to_owned()).collect())
+            }
+            JsonRef::Object(props) => {
+                let props = props.iter().map(|p| Property::new(&p.0, p.1.to_owned()));
+                Json::Object(props.collect())
+            }
+            JsonRef::__Error(err) => Json::__Error(err.clone()),
+        }
+    }
+
+    /// Get a json field, within the document, locatable by ``path``.
+    /// Unlike [Json::get], return a reference to the field.
+    pub fn get<P>(&self, path: &P) -> Result<&JsonRef<'a>>
+    where
+        P: AsPointer + ?Sized,
+    {
+        let mut json = self;
+        for frag in path.as_pointer()?.as_fragments() {
+            json = json[frag.as_str()].to_result()?;
+        }
+        Ok(json)
+    }
+
+    pub fn is_null(&self) -> bool {
+        matches!(self, JsonRef::Null)
+    }
+
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            JsonRef::Bool(s) => Some(*s),
+            _ => None,
+        }
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        match self {
+            JsonRef::Integer(lexeme) => Integral::try_from(*lexeme).ok()?.integer(),
+            _ => None,
+        }
+    }
+
+    pub fn to_float(&self) -> Option<f64> {
+        match self {
+            JsonRef::Float(lexeme) if is_non_finite(lexeme) => lexeme.parse().ok(),
+            JsonRef::Float(lexeme) => Floating::try_from(*lexeme).ok()?.float(),
+            JsonRef::Integer(lexeme) => Integral::try_from(*lexeme).ok()?.float(),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonRef::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, JsonRef::__Error(_))
+    }
+
+    pub fn to_result(&self) -> Result<&JsonRef<'a>> {
+        match self {
+            JsonRef::__Error(err) => Err(err.clone()),
+            _ => Ok(self),
+        }
+    }
+}
+
+// JSON5 Infinity and NaN, that are parsed as floats.
+fn is_non_finite(lexeme: &str) -> bool {
+    lexeme.ends_with("Infinity") || lexeme.ends_with("NaN")
+}
+
+impl<'a> Node<'a> for JsonRef<'a> {
+    type Property = PropertyRef<'a>;
+
+    fn null() -> JsonRef<'a> {
+        JsonRef::Null
+    }
+
+    fn bool(value: bool) -> JsonRef<'a> {
+        JsonRef::Bool(value)
+    }
+
+    fn integer(lexeme: &'a str) -> Result<JsonRef<'a>> {
+        Ok(JsonRef::Integer(lexeme))
+    }
+
+    fn float(lexeme: &'a str) -> Result<JsonRef<'a>> {
+        Ok(JsonRef::Float(lexeme))
+    }
+
+    fn non_finite(lexeme: &'a str, _value: f64) -> JsonRef<'a> {
+        JsonRef::Float(lexeme)
+    }
+
+    fn string(value: Cow<'a, str>) -> JsonRef<'a> {
+        JsonRef::String(value)
+    }
+
+    fn array(items: Vec<JsonRef<'a>>) -> JsonRef<'a> {
+        JsonRef::Array(items)
+    }
+
+    fn as_array_mut(&mut self) -> Option<&mut Vec<JsonRef<'a>>> {
+        match self {
+            JsonRef::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn object(properties: Vec<PropertyRef<'a>>) -> JsonRef<'a> {
+        JsonRef::Object(properties)
+    }
+
+    fn property(key: Cow<'a, str>, value: JsonRef<'a>) -> PropertyRef<'a> {
+        PropertyRef(key, value)
+    }
+
+    fn key<'b>(property: &'b PropertyRef<'a>) -> &'b str {
+        &property.0
+    }
+
+    fn value_mut<'b>(property: &'b mut PropertyRef<'a>) -> &'b mut JsonRef<'a> {
+        &mut property.1
+    }
+
+    fn lazy(_: &'a str, _: Location, _: &Parser, _: usize) -> Option<JsonRef<'a>> {
+        None
+    }
+}
+
+lazy_static! {
+    static ref INDEX_OUT_OF_BOUND: JsonRef<'static> = JsonRef::__Error(
+        Error::IndexOutofBound("json_ref.rs".to_string(), "-1".to_string())
+    );
+    static ref NOT_AN_ARRAY: JsonRef<'static> = JsonRef::__Error(Error::NotAnArray(
+        "json_ref.rs".to_string(),
+        "--na--".to_string()
+    ));
+    static ref NOT_AN_INDEX: JsonRef<'static> = JsonRef::__Error(Error::InvalidIndex(
+        "json_ref.rs".to_string(),
+        "--na--".to_string()
+    ));
+    static ref NOT_A_CONTAINER: JsonRef<'static> = JsonRef::__Error(
+        Error::InvalidContainer("json_ref.rs".to_string(), "--na--".to_string())
+    );
+    static ref PROPERTY_NOT_FOUND: JsonRef<'static> = JsonRef::__Error(
+        Error::PropertyNotFound("json_ref.rs".to_string(), "--na--".to_string())
+    );
+}
+
+impl<'a> Index<isize> for JsonRef<'a> {
+    type Output = JsonRef<'a>;
+
+    fn index(&self, index: isize) -> &JsonRef<'a> {
+        match self {
+            JsonRef::Array(arr) => match ops::normalized_offset(index, arr.len()) {
+                Some(off) => &arr[off],
+                None => &INDEX_OUT_OF_BOUND,
+            },
+            JsonRef::__Error(_) => self,
+            _ => &NOT_AN_ARRAY,
+        }
+    }
+}
+
+impl<'a> Index<&str> for JsonRef<'a> {
+    type Output = JsonRef<'a>;
+
+    fn index(&self, index: &str) -> &JsonRef<'a> {
+        match self {
+            JsonRef::Object(obj) => match obj.binary_search_by(|p| p.as_key().cmp(index))
+            {
+                Ok(off) => obj[off].as_value(),
+                Err(_) => &PROPERTY_NOT_FOUND,
+            },
+            JsonRef::Array(arr) => match index.parse::<isize>() {
+                Ok(n) => match ops::normalized_offset(n, arr.len()) {
+                    Some(off) => &arr[off],
+                    None => &INDEX_OUT_OF_BOUND,
+                },
+                Err(_) => &NOT_AN_INDEX,
+            },
+            JsonRef::__Error(_) => self,
+            _ => &NOT_A_CONTAINER,
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "json_ref_test.rs"]
+mod json_ref_test;
@@ -0,0 +1,112 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::borrow::Cow;
+
+use super::*;
+use crate::parse::{Duplicates, Profile};
+
+#[test]
+fn test_json_ref_borrowed() {
+    let text = r#"{"a": "hello", "b\tc": "x\u00e9", "d": [1, -2.5e3, true, null]}"#;
+    let value = JsonRef::parse(text).unwrap();
+
+    match &value {
+        JsonRef::Object(props) => {
+            let keys: Vec<&str> = props.iter().map(|p| p.as_key()).collect();
+            assert_eq!(keys, vec!["a", "b\tc", "d"]);
+            assert!(matches!(props[0].0, Cow::Borrowed("a")));
+            assert!(matches!(props[1].0, Cow::Owned(_)));
+            assert!(matches!(props[2].0, Cow::Borrowed("d")));
+        }
+        _ => unreachable!(),
+    }
+    assert!(matches!(value["a"], JsonRef::String(Cow::Borrowed("hello"))));
+    assert!(matches!(value["b\tc"], JsonRef::String(Cow::Owned(_))));
+    assert_eq!(value["b\tc"].as_str(), Some("xé"));
+    assert_eq!(value["d"][0], JsonRef::Integer("1"));
+    assert_eq!(value["d"][1], JsonRef::Float("-2.5e3"));
+    assert_eq!(value["d"][1].to_float(), Some(-2500.0));
+    assert_eq!(value["d"][-2].to_bool(), Some(true));
+    assert!(value["d"][3].is_null());
+
+    let text = "{$key: 'v', \\u0061b: 0x1F}";
+    let value = Parser::new().profile(Profile::Json5).parse_ref(text).unwrap();
+    match &value {
+        JsonRef::Object(props) => {
+            assert!(matches!(props[0].0, Cow::Borrowed("$key")));
+            assert!(matches!(props[1].0, Cow::Owned(_)));
+            assert_eq!(props[1].as_key(), "ab");
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(value["ab"].to_integer(), Some(31));
+}
+
+#[test]
+fn test_json_ref_get() {
+    let text = r#"{"a": {"b": [10, {"c~d": "x", "e/f": 2}]}}"#;
+    let value = JsonRef::parse(text).unwrap();
+
+    assert_eq!(value.get("").unwrap(), &value);
+    assert_eq!(value.get("/a/b/0").unwrap().to_integer(), Some(10));
+    assert_eq!(value.get("/a/b/-1/c~0d").unwrap().as_str(), Some("x"));
+    assert_eq!(value.get("/a/b/1/e~1f").unwrap().to_integer(), Some(2));
+
+    let res = value.get("/a/x");
+    assert!(matches!(res, Err(Error::PropertyNotFound(_, _))));
+    let res = value.get("/a/b/2");
+    assert!(matches!(res, Err(Error::IndexOutofBound(_, _))));
+    let res = value.get("/a/b/x");
+    assert!(matches!(res, Err(Error::InvalidIndex(_, _))));
+    let res = value.get("/a/b/0/c");
+    assert!(matches!(res, Err(Error::InvalidContainer(_, _))));
+    let res = value.get("a");
+    assert!(matches!(res, Err(Error::JptrFail(_, _))));
+
+    assert!(value["x"]["y"].is_error());
+    assert!(value["a"][0].is_error());
+}
+
+#[test]
+fn test_json_ref_to_owned() {
+    let texts = [
+        "null",
+        "[true, false, 0, -10, 1.5, 1e300, \"\"]",
+        r#"{"z": 1, "a": {"b": ["😀", "\"q\""]}, "m": []}"#,
+    ];
+    for text in texts.iter() {
+        let value = JsonRef::parse(text).unwrap();
+        assert_eq!(value.to_owned(), text.parse::<Json>().unwrap(), "{}", text);
+    }
+
+    let parser = Parser::new().profile(Profile::Json5);
+    let text = "[Infinity, -Infinity, 0x10, .5, 'a\\\nb', {k: +1,}]";
+    let value = parser.parse_ref(text).unwrap();
+    assert_eq!(value.to_owned(), parser.parse(text).unwrap());
+    assert_eq!(value[1].to_float(), Some(f64::NEG_INFINITY));
+    assert!(value.to_owned()[0].to_float().unwrap().is_infinite());
+}
+
+#[test]
+fn test_json_ref_parser() {
+    let text = r#"{"a": 1, "a": 2, "a": 3}"#;
+    let parser = Parser::new().duplicates(Duplicates::Collect);
+    let value = parser.parse_ref(text).unwrap();
+    assert_eq!(value.to_owned(), parser.parse(text).unwrap());
+    assert_eq!(value["a"][2], JsonRef::Integer("3"));
+
+    let parser = Parser::new().duplicates(Duplicates::FirstWins);
+    assert_eq!(parser.parse_ref(text).unwrap()["a"], JsonRef::Integer("1"));
+
+    let err = Parser::new().duplicates(Duplicates::Error).parse_ref(text).unwrap_err();
+    assert_eq!(err.location().unwrap().offset(), 9);
+
+    let err = Parser::new().max_depth(2).parse_ref("[[[1]]]").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+
+    let err = JsonRef::parse(r#"{"a": "b"#).unwrap_err();
+    assert!(matches!(err, Error::ParseFail(_, _, _)));
+    let parser = Parser::new().profile(Profile::Strict);
+    let err = parser.parse_ref("[1, 2] x").unwrap_err();
+    assert!(matches!(err, Error::ParseFail(_, _, _)));
+}
@@ -0,0 +1,974 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+//! Module jsonpath implements [JSONPath RFC spec.], to query JSON documents.
+//!
+//! Unlike [JSON Pointer], that addresses a single value, a JSONPath
+//! query selects a list of nodes from the document, using wildcards,
+//! recursive descent, array slices and filter expressions. Queries are
+//! compiled once into [JsonPath], and can be evaluated over any number
+//! of documents.
+//!
+//! ```
+//! use jsondata::{jsonpath::JsonPath, Json};
+//!
+//! let text = r#"{"orders": [{"id": 1, "total": 50}, {"id": 2, "total": 150}]}"#;
+//! let json: Json = text.parse().unwrap();
+//!
+//! let path: JsonPath = "$.orders[?@.total > 100].id".parse().unwrap();
+//! let nodes = path.query(&json);
+//! assert_eq!(nodes.len(), 1);
+//! assert_eq!(nodes[0].value(), &Json::new(2));
+//! assert_eq!(nodes[0].path(), "$['orders'][1]['id']");
+//! ```
+//!
+//! All functions defined by the specification, ``length()``, ``count()``,
+//! ``match()``, ``search()`` and ``value()``, are supported. Regular
+//! expressions for ``match()`` and ``search()`` follow [I-Regexp]. An
+//! invalid literal pattern fails to compile the path, while an invalid
+//! pattern taken from the document never matches. Comparison of numbers
+//! and of strings use [Json]'s [Ord] implementation, numbers of different
+//! types are compared as floats.
+//!
+//! [JSONPath RFC spec.]: https://www.rfc-editor.org/rfc/rfc9535
+//! [JSON Pointer]: crate::jptr
+//! [I-Regexp]: https://www.rfc-editor.org/rfc/rfc9485
+
+use std::{borrow::Cow, cmp::Ordering, fmt, iter::FromIterator, str::FromStr};
+
+use crate::{iregexp::Regex, jptr::Pointer, json::Json, Error, Result};
+
+// Array index and slice parameters are limited to I-JSON integers.
+const MAX_INT: i64 = (1 << 53) - 1;
+
+/// JsonPath is a compiled JSONPath query, parse the query text using
+/// [str::parse] and evaluate it using [JsonPath::query]. Query text is
+/// validated as per the specification, including the types of function
+/// expressions, and fails with [Error::JpathFail].
+#[derive(Clone, Debug)]
+pub struct JsonPath {
+    text: String,
+    segments: Vec<Segment>,
+}
+
+/// Node selected by [JsonPath::query], a value within the document along
+/// with its location.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node<'a> {
+    steps: Vec<Step<'a>>,
+    value: &'a Json,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Step<'a> {
+    Key(&'a str),
+    Index(usize),
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+    Child(Vec<Selector>),
+    Descendant(Vec<Selector>),
+}
+
+#[derive(Clone, Debug)]
+enum Selector {
+    Name(String),
+    Wildcard,
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Filter(Box<Expr>),
+}
+
+#[derive(Clone, Debug)]
+enum Expr {
+    Or(Vec<Expr>),
+    And(Vec<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, Op, Operand),
+    Exists(Query),
+    Test(Function),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+// Comparable, and function argument.
+#[derive(Clone, Debug)]
+enum Operand {
+    Literal(Box<Json>),
+    Query(Query),
+    Function(Function),
+}
+
+#[derive(Clone, Debug)]
+struct Query {
+    absolute: bool,
+    segments: Vec<Segment>,
+}
+
+#[derive(Clone, Debug)]
+struct Function {
+    name: Func,
+    args: Vec<Operand>,
+    // Compiled pattern of match() and search(), if it is a literal.
+    regex: Option<Regex>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Func {
+    Length,
+    Count,
+    Match,
+    Search,
+    Value,
+}
+
+impl fmt::Display for Func {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Func::Length => "length",
+            Func::Count => "count",
+            Func::Match => "match",
+            Func::Search => "search",
+            Func::Value => "value",
+        };
+        write!(f, "{}()", name)
+    }
+}
+
+impl FromStr for JsonPath {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<JsonPath> {
+        let mut compiler = Compiler { text, off: 0 };
+        let query = compiler.query()?;
+        match query.absolute {
+            true if compiler.off == text.len() => {
+                Ok(JsonPath { text: text.to_string(), segments: query.segments })
+            }
+            true => compiler.fail("unexpected text"),
+            false => compiler.fail("query should start with $"),
+        }
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl JsonPath {
+    /// Evaluate the query over ``doc``, and return the selected nodes in
+    /// document order.
+    pub fn query<'a>(&self, doc: &'a Json) -> Vec<Node<'a>> {
+        let node = Node { steps: vec![], value: doc.resolve() };
+        select(&self.segments, doc, node)
+    }
+
+    /// Same as [JsonPath::query], but return only the selected values.
+    pub fn values<'a>(&self, doc: &'a Json) -> Vec<&'a Json> {
+        self.query(doc).into_iter().map(|node| node.value).collect()
+    }
+}
+
+impl<'a> Node<'a> {
+    /// Return the value of the node.
+    pub fn value(&self) -> &'a Json {
+        self.value
+    }
+
+    /// Return the location of node, as normalized path, like
+    /// ``$['a'][0]``.
+    pub fn path(&self) -> String {
+        let mut outs = "$".to_string();
+        for step in self.steps.iter() {
+            match step {
+                Step::Key(key) => {
+                    outs.push_str("['");
+                    for ch in key.chars() {
+                        match ch {
+                            '\x08' => outs.push_str("\\b"),
+                            '\x0c' => outs.push_str("\\f"),
+                            '\n' => outs.push_str("\\n"),
+                            '\r' => outs.push_str("\\r"),
+                            '\t' => outs.push_str("\\t"),
+                            '\'' => outs.push_str("\\'"),
+                            '\\' => outs.push_str("\\\\"),
+                            '\x00'..='\x1f' => {
+                                outs.push_str(&format!("\\u{:04x}", ch as u32))
+                            }
+                            ch => outs.push(ch),
+                        }
+                    }
+                    outs.push_str("']");
+                }
+                Step::Index(index) => outs.push_str(&format!("[{}]", index)),
+            }
+        }
+        outs
+    }
+
+    /// Return the location of node, as JSON pointer.
+    pub fn to_pointer(&self) -> Pointer {
+        Pointer::from_iter(self.steps.iter().map(|step| match step {
+            Step::Key(key) => key.to_string(),
+            Step::Index(index) => index.to_string(),
+        }))
+    }
+}
+
+fn select<'a>(segments: &[Segment], root: &'a Json, node: Node<'a>) -> Vec<Node<'a>> {
+    let mut nodes = vec![node];
+    for segment in segments.iter() {
+        let mut outs = vec![];
+        for node in nodes.iter() {
+            match segment {
+                Segment::Child(selectors) => {
+                    for selector in selectors.iter() {
+                        selector.apply(root, node, &mut outs);
+                    }
+                }
+                Segment::Descendant(selectors) => {
+                    descend(selectors, root, node, &mut outs)
+                }
+            }
+        }
+        nodes = outs;
+    }
+    nodes
+}
+
+// Apply selectors to ``node`` and all its descendants, in document order.
+fn descend<'a>(
+    selectors: &[Selector],
+    root: &'a Json,
+    node: &Node<'a>,
+    outs: &mut Vec<Node<'a>>,
+) {
+    for selector in selectors.iter() {
+        selector.apply(root, node, outs);
+    }
+    for child in children(node).iter() {
+        descend(selectors, root, child, outs);
+    }
+}
+
+fn children<'a>(node: &Node<'a>) -> Vec<Node<'a>> {
+    match node.value {
+        Json::Array(arr) => {
+            (0..arr.len()).map(|i| node.child(Step::Index(i), &arr[i])).collect()
+        }
+        Json::Object(props) => {
+            let iter = props.iter();
+            iter.map(|p| node.child(Step::Key(p.as_key()), p.as_value())).collect()
+        }
+        _ => vec![],
+    }
+}
+
+impl<'a> Node<'a> {
+    fn child(&self, step: Step<'a>, value: &'a Json) -> Node<'a> {
+        let mut steps = self.steps.clone();
+        steps.push(step);
+        Node { steps, value: value.resolve() }
+    }
+}
+
+impl Selector {
+    fn apply<'a>(&self, root: &'a Json, node: &Node<'a>, outs: &mut Vec<Node<'a>>) {
+        match (self, node.value) {
+            (Selector::Name(name), Json::Object(props)) => {
+                if let Ok(n) = props.binary_search_by(|p| p.as_key().cmp(name)) {
+                    outs.push(
+                        node.child(Step::Key(props[n].as_key()), props[n].as_value()),
+                    )
+                }
+            }
+            (Selector::Wildcard, _) => outs.extend(children(node)),
+            (Selector::Index(index), Json::Array(arr)) => {
+                let len = arr.len() as i64;
+                let index = if *index < 0 { len + index } else { *index };
+                if (0..len).contains(&index) {
+                    let index = index as usize;
+                    outs.push(node.child(Step::Index(index), &arr[index]))
+                }
+            }
+            (Selector::Slice(start, end, step), Json::Array(arr)) => {
+                for i in slice_indices(arr.len() as i64, *start, *end, *step) {
+                    outs.push(node.child(Step::Index(i), &arr[i]))
+                }
+            }
+            (Selector::Filter(expr), _) => {
+                let iter = children(node).into_iter();
+                outs.extend(iter.filter(|child| expr.eval(root, child.value)))
+            }
+            (_, _) => (),
+        }
+    }
+}
+
+// Return the array indices selected by slice, refer RFC 9535 section 2.3.4.
+fn slice_indices(
+    len: i64,
+    start: Option<i64>,
+    end: Option<i64>,
+    step: Option<i64>,
+) -> Vec<usize> {
+    let normalize = |i: i64| if i >= 0 { i } else { len + i };
+    let step = step.unwrap_or(1);
+    let mut indices = vec![];
+    if step > 0 {
+        let lower = normalize(start.unwrap_or(0)).clamp(0, len);
+        let upper = normalize(end.unwrap_or(len)).clamp(0, len);
+        let mut i = lower;
+        while i < upper {
+            indices.push(i as usize);
+            i += step;
+        }
+    } else if step < 0 {
+        let upper = normalize(start.unwrap_or(len - 1)).clamp(-1, len - 1);
+        let lower = match end {
+            Some(end) => normalize(end).clamp(-1, len - 1),
+            None => -1,
+        };
+        let mut i = upper;
+        while lower < i {
+            indices.push(i as usize);
+            i += step;
+        }
+    }
+    indices
+}
+
+impl Expr {
+    fn eval(&self, root: &Json, current: &Json) -> bool {
+        match self {
+            Expr::Or(exprs) => exprs.iter().any(|expr| expr.eval(root, current)),
+            Expr::And(exprs) => exprs.iter().all(|expr| expr.eval(root, current)),
+            Expr::Not(expr) => !expr.eval(root, current),
+            Expr::Compare(left, op, right) => {
+                let (a, b) = (left.value(root, current), right.value(root, current));
+                compare(a.as_deref(), *op, b.as_deref())
+            }
+            Expr::Exists(query) => !query.nodes(root, current).is_empty(),
+            Expr::Test(function) => function.test(root, current),
+        }
+    }
+}
+
+impl Query {
+    fn nodes<'a>(&self, root: &'a Json, current: &'a Json) -> Vec<Node<'a>> {
+        let value = if self.absolute { root.resolve() } else { current };
+        select(&self.segments, root, Node { steps: vec![], value })
+    }
+
+    fn is_singular(&self) -> bool {
+        self.segments.iter().all(|segment| match segment {
+            Segment::Child(selectors) => {
+                matches!(selectors.as_slice(), [Selector::Name(_)] | [Selector::Index(_)])
+            }
+            Segment::Descendant(_) => false,
+        })
+    }
+}
+
+impl Operand {
+    // Evaluate operand of ValueType, None is Nothing.
+    fn value<'a>(&'a self, root: &'a Json, current: &'a Json) -> Option<Cow<'a, Json>> {
+        match self {
+            Operand::Literal(value) => Some(Cow::Borrowed(value.as_ref())),
+            Operand::Query(query) => match query.nodes(root, current).as_slice() {
+                [node] => Some(Cow::Borrowed(node.value)),
+                _ => None,
+            },
+            Operand::Function(function) => function.value(root, current),
+        }
+    }
+
+    // Evaluate operand of NodesType.
+    fn nodes<'a>(&'a self, root: &'a Json, current: &'a Json) -> Vec<Node<'a>> {
+        match self {
+            Operand::Query(query) => query.nodes(root, current),
+            _ => vec![],
+        }
+    }
+}
+
+impl Function {
+    fn value<'a>(&'a self, root: &'a Json, current: &'a Json) -> Option<Cow<'a, Json>> {
+        match self.name {
+            Func::Length => {
+                let n = match self.args[0].value(root, current)?.as_ref() {
+                    Json::String(s) => s.chars().count(),
+                    Json::Array(arr) => arr.len(),
+                    Json::Object(props) => props.len(),
+                    _ => return None,
+                };
+                Some(Cow::Owned(Json::new(n)))
+            }
+            Func::Count => {
+                let n = self.args[0].nodes(root, current).len();
+                Some(Cow::Owned(Json::new(n)))
+            }
+            Func::Value => match self.args[0].nodes(root, current).as_slice() {
+                [node] => Some(Cow::Borrowed(node.value)),
+                _ => None,
+            },
+            Func::Match | Func::Search => None,
+        }
+    }
+
+    fn test(&self, root: &Json, current: &Json) -> bool {
+        let text = self.args[0].value(root, current);
+        let text = match text.as_deref() {
+            Some(Json::String(text)) => text,
+            _ => return false,
+        };
+        if let Some(re) = &self.regex {
+            return re.is_match(text);
+        }
+        let pattern = self.args[1].value(root, current);
+        match pattern.as_deref() {
+            Some(Json::String(pattern)) => {
+                match Regex::new(pattern, self.name == Func::Match) {
+                    Ok(re) => re.is_match(text),
+                    Err(_) => false,
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+fn compare(a: Option<&Json>, op: Op, b: Option<&Json>) -> bool {
+    match op {
+        Op::Eq => is_equal(a, b),
+        Op::Ne => !is_equal(a, b),
+        Op::Lt => is_less(a, b),
+        Op::Le => is_less(a, b) || is_equal(a, b),
+        Op::Gt => is_less(b, a),
+        Op::Ge => is_less(b, a) || is_equal(a, b),
+    }
+}
+
+fn is_equal(a: Option<&Json>, b: Option<&Json>) -> bool {
+    match (a, b) {
+        (None, None) => true,
+        (Some(a), Some(b)) => match cmp_numbers(a, b) {
+            Some(ord) => ord == Ordering::Equal,
+            None => a == b,
+        },
+        (_, _) => false,
+    }
+}
+
+fn is_less(a: Option<&Json>, b: Option<&Json>) -> bool {
+    match (a, b) {
+        (Some(a @ Json::String(_)), Some(b @ Json::String(_))) => a < b,
+        (Some(a), Some(b)) => cmp_numbers(a, b) == Some(Ordering::Less),
+        (_, _) => false,
+    }
+}
+
+fn cmp_numbers(a: &Json, b: &Json) -> Option<Ordering> {
+    match (a, b) {
+        (Json::Integer(_), Json::Integer(_)) => Some(a.cmp(b)),
+        (Json::Integer(_) | Json::Float(_), Json::Integer(_) | Json::Float(_)) => {
+            a.to_float()?.partial_cmp(&b.to_float()?)
+        }
+        (_, _) => None,
+    }
+}
+
+// Compiler parses JSONPath query text, refer to the ABNF in RFC 9535.
+struct Compiler<'a> {
+    text: &'a str,
+    off: usize,
+}
+
+impl<'a> Compiler<'a> {
+    fn fail<T>(&self, msg: &str) -> Result<T> {
+        err_at!(JpathFail, msg: "{} at offset {} in {:?}", msg, self.off, self.text)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.off..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = self.peek()?;
+        self.off += ch.len_utf8();
+        Some(ch)
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let ok = self.text[self.off..].starts_with(token);
+        if ok {
+            self.off += token.len();
+        }
+        ok
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        match self.eat(token) {
+            true => Ok(()),
+            false => self.fail(&format!("expected {:?}", token)),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let text = &self.text[self.off..];
+        let trimmed = text.trim_start_matches([' ', '\t', '\n', '\r']);
+        self.off += text.len() - trimmed.len();
+    }
+
+    // Parse absolute query starting with "$", or relative query starting
+    // with "@".
+    fn query(&mut self) -> Result<Query> {
+        let absolute = match self.bump() {
+            Some('$') => true,
+            Some('@') => false,
+            _ => self.fail("expected $ or @")?,
+        };
+        let mut segments = vec![];
+        loop {
+            let off = self.off;
+            self.skip_whitespace();
+            match self.peek() {
+                Some('.') | Some('[') => segments.push(self.segment()?),
+                _ => {
+                    self.off = off;
+                    break;
+                }
+            }
+        }
+        Ok(Query { absolute, segments })
+    }
+
+    fn segment(&mut self) -> Result<Segment> {
+        if self.eat("..") {
+            let selectors = match self.peek() {
+                Some('[') => self.bracketed()?,
+                Some('*') => {
+                    self.bump();
+                    vec![Selector::Wildcard]
+                }
+                _ => vec![Selector::Name(self.member_name()?)],
+            };
+            Ok(Segment::Descendant(selectors))
+        } else if self.eat(".") {
+            match self.peek() {
+                Some('*') => {
+                    self.bump();
+                    Ok(Segment::Child(vec![Selector::Wildcard]))
+                }
+                _ => Ok(Segment::Child(vec![Selector::Name(self.member_name()?)])),
+            }
+        } else {
+            Ok(Segment::Child(self.bracketed()?))
+        }
+    }
+
+    fn member_name(&mut self) -> Result<String> {
+        let is_first = |ch: char| ch.is_ascii_alphabetic() || ch == '_' || !ch.is_ascii();
+        match self.peek() {
+            Some(ch) if is_first(ch) => (),
+            _ => self.fail("expected member name")?,
+        }
+        let start = self.off;
+        while let Some(ch) = self.peek().filter(|ch| is_first(*ch) || ch.is_ascii_digit())
+        {
+            self.off += ch.len_utf8();
+        }
+        Ok(self.text[start..self.off].to_string())
+    }
+
+    fn bracketed(&mut self) -> Result<Vec<Selector>> {
+        self.expect("[")?;
+        self.skip_whitespace();
+        let mut selectors = vec![self.selector()?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat(",") {
+                break;
+            }
+            self.skip_whitespace();
+            selectors.push(self.selector()?);
+        }
+        self.expect("]")?;
+        Ok(selectors)
+    }
+
+    fn selector(&mut self) -> Result<Selector> {
+        match self.peek() {
+            Some('\'') | Some('"') => Ok(Selector::Name(self.string()?)),
+            Some('*') => {
+                self.bump();
+                Ok(Selector::Wildcard)
+            }
+            Some('?') => {
+                self.bump();
+                self.skip_whitespace();
+                Ok(Selector::Filter(Box::new(self.logical_or()?)))
+            }
+            Some(':') => self.slice(None),
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => {
+                let start = self.integer()?;
+                let off = self.off;
+                self.skip_whitespace();
+                match self.peek() {
+                    Some(':') => self.slice(Some(start)),
+                    _ => {
+                        self.off = off;
+                        Ok(Selector::Index(start))
+                    }
+                }
+            }
+            _ => self.fail("expected selector"),
+        }
+    }
+
+    fn slice(&mut self, start: Option<i64>) -> Result<Selector> {
+        self.expect(":")?;
+        self.skip_whitespace();
+        let end = self.optional_integer()?;
+        self.skip_whitespace();
+        let step = match self.eat(":") {
+            true => {
+                self.skip_whitespace();
+                self.optional_integer()?
+            }
+            false => None,
+        };
+        Ok(Selector::Slice(start, end, step))
+    }
+
+    fn optional_integer(&mut self) -> Result<Option<i64>> {
+        match self.peek() {
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => Ok(Some(self.integer()?)),
+            _ => Ok(None),
+        }
+    }
+
+    fn integer(&mut self) -> Result<i64> {
+        let start = self.off;
+        let negative = self.eat("-");
+        let digits = self.digits();
+        let text = &self.text[start..self.off];
+        match digits {
+            "" => self.fail("expected integer"),
+            "0" if negative => self.fail("invalid integer -0"),
+            digits if digits.len() > 1 && digits.starts_with('0') => {
+                self.fail("leading zero in integer")
+            }
+            _ => match text.parse::<i64>() {
+                Ok(n) if (-MAX_INT..=MAX_INT).contains(&n) => Ok(n),
+                _ => self.fail("integer out of range"),
+            },
+        }
+    }
+
+    fn digits(&mut self) -> &'a str {
+        let text = &self.text[self.off..];
+        let n = text.bytes().take_while(u8::is_ascii_digit).count();
+        self.off += n;
+        &text[..n]
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let quote = self.bump();
+        let mut outs = String::new();
+        loop {
+            match self.bump() {
+                None => self.fail("unterminated string")?,
+                ch if ch == quote => break,
+                Some('\\') => match self.bump() {
+                    Some('b') => outs.push('\x08'),
+                    Some('f') => outs.push('\x0c'),
+                    Some('n') => outs.push('\n'),
+                    Some('r') => outs.push('\r'),
+                    Some('t') => outs.push('\t'),
+                    Some('/') => outs.push('/'),
+                    Some('\\') => outs.push('\\'),
+                    ch @ Some('\'' | '"') if ch == quote => outs.push(quote.unwrap()),
+                    Some('u') => outs.push(self.unicode()?),
+                    _ => self.fail("invalid escape in string")?,
+                },
+                Some(ch) if ch < '\x20' => self.fail("control character in string")?,
+                Some(ch) => outs.push(ch),
+            }
+        }
+        Ok(outs)
+    }
+
+    fn unicode(&mut self) -> Result<char> {
+        let high = self.hex4()?;
+        let code = match high {
+            0xD800..=0xDBFF if self.eat("\\u") => match self.hex4()? {
+                low @ 0xDC00..=0xDFFF => {
+                    0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                }
+                _ => self.fail("invalid surrogate pair")?,
+            },
+            _ => high,
+        };
+        match char::from_u32(code) {
+            Some(ch) => Ok(ch),
+            None => self.fail("invalid unicode escape"),
+        }
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let text = self.text[self.off..].get(..4).unwrap_or("");
+        match text.bytes().all(|b| b.is_ascii_hexdigit()) && text.len() == 4 {
+            true => {
+                self.off += 4;
+                Ok(u32::from_str_radix(text, 16).unwrap())
+            }
+            false => self.fail("invalid unicode escape"),
+        }
+    }
+
+    fn logical_or(&mut self) -> Result<Expr> {
+        let mut exprs = vec![self.logical_and()?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat("||") {
+                break;
+            }
+            self.skip_whitespace();
+            exprs.push(self.logical_and()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::Or(exprs) })
+    }
+
+    fn logical_and(&mut self) -> Result<Expr> {
+        let mut exprs = vec![self.basic()?];
+        loop {
+            self.skip_whitespace();
+            if !self.eat("&&") {
+                break;
+            }
+            self.skip_whitespace();
+            exprs.push(self.basic()?);
+        }
+        Ok(if exprs.len() == 1 { exprs.remove(0) } else { Expr::And(exprs) })
+    }
+
+    fn basic(&mut self) -> Result<Expr> {
+        if self.eat("!") {
+            self.skip_whitespace();
+            let expr = match self.peek() {
+                Some('(') => self.paren()?,
+                _ => match self.operand()? {
+                    Operand::Query(query) => Expr::Exists(query),
+                    Operand::Function(function) => self.test(function)?,
+                    Operand::Literal(_) => self.fail("expected query or function")?,
+                },
+            };
+            return Ok(Expr::Not(Box::new(expr)));
+        } else if self.peek() == Some('(') {
+            return self.paren();
+        }
+
+        let left = self.operand()?;
+        let off = self.off;
+        self.skip_whitespace();
+        match self.comparison_op() {
+            Some(op) => {
+                self.skip_whitespace();
+                let right = self.operand()?;
+                Ok(Expr::Compare(self.comparable(left)?, op, self.comparable(right)?))
+            }
+            None => {
+                self.off = off;
+                match left {
+                    Operand::Query(query) => Ok(Expr::Exists(query)),
+                    Operand::Function(function) => self.test(function),
+                    Operand::Literal(_) => self.fail("expected comparison")?,
+                }
+            }
+        }
+    }
+
+    fn paren(&mut self) -> Result<Expr> {
+        self.expect("(")?;
+        self.skip_whitespace();
+        let expr = self.logical_or()?;
+        self.skip_whitespace();
+        self.expect(")")?;
+        Ok(expr)
+    }
+
+    fn comparison_op(&mut self) -> Option<Op> {
+        let ops = [
+            ("==", Op::Eq),
+            ("!=", Op::Ne),
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+        ];
+        ops.iter().find(|(token, _)| self.eat(token)).map(|(_, op)| *op)
+    }
+
+    // Function used as test expression, must return LogicalType.
+    fn test(&self, function: Function) -> Result<Expr> {
+        match function.name {
+            Func::Match | Func::Search => Ok(Expr::Test(function)),
+            _ => self.fail(&format!("{} is not a logical function", function.name)),
+        }
+    }
+
+    // Comparable must be a literal, singular query, or function that
+    // returns ValueType.
+    fn comparable(&self, operand: Operand) -> Result<Operand> {
+        match &operand {
+            Operand::Query(query) if !query.is_singular() => {
+                self.fail("comparison with non-singular query")
+            }
+            Operand::Function(f) if matches!(f.name, Func::Match | Func::Search) => {
+                self.fail(&format!("comparison with {}", f.name))
+            }
+            _ => Ok(operand),
+        }
+    }
+
+    fn operand(&mut self) -> Result<Operand> {
+        match self.peek() {
+            Some('$') | Some('@') => Ok(Operand::Query(self.query()?)),
+            Some('\'') | Some('"') => {
+                Ok(Operand::Literal(Box::new(Json::new(self.string()?))))
+            }
+            Some(ch) if ch == '-' || ch.is_ascii_digit() => {
+                Ok(Operand::Literal(Box::new(self.number()?)))
+            }
+            Some(ch) if ch.is_ascii_lowercase() => {
+                let start = self.off;
+                while let Some(ch) = self.peek() {
+                    match ch {
+                        'a'..='z' | '0'..='9' | '_' => self.off += 1,
+                        _ => break,
+                    }
+                }
+                let name = &self.text[start..self.off];
+                match (name, self.peek()) {
+                    (name, Some('(')) => Ok(Operand::Function(self.function(name)?)),
+                    ("true", _) => Ok(Operand::Literal(Box::new(Json::new(true)))),
+                    ("false", _) => Ok(Operand::Literal(Box::new(Json::new(false)))),
+                    ("null", _) => Ok(Operand::Literal(Box::new(Json::Null))),
+                    (name, _) => self.fail(&format!("unexpected {:?}", name)),
+                }
+            }
+            _ => self.fail("expected literal, query or function"),
+        }
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.off;
+        self.eat("-");
+        let digits = self.digits();
+        if digits.is_empty() || (digits.len() > 1 && digits.starts_with('0')) {
+            self.fail("invalid number")?;
+        }
+        let mut is_float = false;
+        if self.eat(".") {
+            is_float = true;
+            if self.digits().is_empty() {
+                self.fail("invalid fraction in number")?;
+            }
+        }
+        if self.eat("e") || self.eat("E") {
+            is_float = true;
+            if !self.eat("-") {
+                self.eat("+");
+            }
+            if self.digits().is_empty() {
+                self.fail("invalid exponent in number")?;
+            }
+        }
+        let text = &self.text[start..self.off];
+        match is_float {
+            true => Ok(Json::new(err_at!(JpathFail, text.parse::<f64>())?)),
+            false => match text.parse::<i128>() {
+                Ok(n) => Ok(Json::new(n)),
+                Err(_) => self.fail("integer out of range"),
+            },
+        }
+    }
+
+    fn function(&mut self, name: &str) -> Result<Function> {
+        let name = match name {
+            "length" => Func::Length,
+            "count" => Func::Count,
+            "match" => Func::Match,
+            "search" => Func::Search,
+            "value" => Func::Value,
+            _ => self.fail(&format!("unknown function {}()", name))?,
+        };
+        self.expect("(")?;
+        self.skip_whitespace();
+        let mut args = vec![];
+        if self.peek() != Some(')') {
+            args.push(self.operand()?);
+            loop {
+                self.skip_whitespace();
+                if !self.eat(",") {
+                    break;
+                }
+                self.skip_whitespace();
+                args.push(self.operand()?);
+            }
+        }
+        self.skip_whitespace();
+        self.expect(")")?;
+
+        // check the number of arguments, and their types.
+        let (n, nodes_type) = match name {
+            Func::Length => (1, false),
+            Func::Count | Func::Value => (1, true),
+            Func::Match | Func::Search => (2, false),
+        };
+        if args.len() != n {
+            self.fail(&format!("{} expects {} arguments", name, n))?;
+        }
+        for arg in args.iter() {
+            let ok = match arg {
+                Operand::Query(_) if nodes_type => true,
+                Operand::Query(query) => query.is_singular(),
+                Operand::Literal(_) => !nodes_type,
+                Operand::Function(f) => {
+                    !nodes_type && !matches!(f.name, Func::Match | Func::Search)
+                }
+            };
+            if !ok {
+                self.fail(&format!("invalid argument type for {}", name))?;
+            }
+        }
+
+        // only match() and search() take a second argument, the pattern.
+        let regex = match args.get(1) {
+            Some(Operand::Literal(lit)) => match lit.as_ref() {
+                Json::String(pattern) => match Regex::new(pattern, name == Func::Match) {
+                    Ok(re) => Some(re),
+                    Err(err) => self.fail(&format!("invalid regexp, {}", err))?,
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+        Ok(Function { name, args, regex })
+    }
+}
+
+#[cfg(test)]
+#[path = "jsonpath_test.rs"]
+mod jsonpath_test;
@@ -0,0 +1,211 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use super::*;
+
+// Example document from RFC 9535 section 1.5.
+const STORE: &str = r#"
+{ "store": {
+    "book": [
+      { "category": "reference",
+        "author": "Nigel Rees",
+        "title": "Sayings of the Century",
+        "price": 8.95
+      },
+      { "category": "fiction",
+        "author": "Evelyn Waugh",
+        "title": "Sword of Honour",
+        "price": 12.99
+      },
+      { "category": "fiction",
+        "author": "Herman Melville",
+        "title": "Moby Dick",
+        "isbn": "0-553-21311-3",
+        "price": 8.99
+      },
+      { "category": "fiction",
+        "author": "J. R. R. Tolkien",
+        "title": "The Lord of the Rings",
+        "isbn": "0-395-19395-8",
+        "price": 22.99
+      }
+    ],
+    "bicycle": {
+      "color": "red",
+      "price": 399
+    }
+  }
+}
+"#;
+
+fn query(json: &Json, path: &str) -> Vec<Json> {
+    let path: JsonPath = path.parse().unwrap();
+    path.values(json).into_iter().cloned().collect()
+}
+
+fn paths(json: &Json, path: &str) -> Vec<String> {
+    let path: JsonPath = path.parse().unwrap();
+    path.query(json).iter().map(|node| node.path()).collect()
+}
+
+#[test]
+fn test_jsonpath_store() {
+    let json: Json = STORE.parse().unwrap();
+    let authors: Vec<Json> =
+        ["Nigel Rees", "Evelyn Waugh", "Herman Melville", "J. R. R. Tolkien"]
+            .iter()
+            .map(|s| Json::new(*s))
+            .collect();
+
+    assert_eq!(query(&json, "$.store.book[*].author"), authors);
+    assert_eq!(query(&json, "$..author"), authors);
+    assert_eq!(query(&json, "$.store.*").len(), 2);
+    assert_eq!(query(&json, "$.store..price").len(), 5);
+    assert_eq!(query(&json, "$..book[2].title"), vec![Json::new("Moby Dick")]);
+    assert_eq!(
+        query(&json, "$..book[-1].title"),
+        vec![Json::new("The Lord of the Rings")]
+    );
+    assert_eq!(query(&json, "$..book[0,1].author"), authors[..2].to_vec());
+    assert_eq!(query(&json, "$..book[:2].author"), authors[..2].to_vec());
+    assert_eq!(query(&json, "$..book[?@.isbn].author"), authors[2..].to_vec());
+    assert_eq!(
+        query(&json, "$..book[?@.price<10].author"),
+        vec![authors[0].clone(), authors[2].clone()]
+    );
+    assert_eq!(query(&json, "$..*").len(), 27);
+    assert_eq!(query(&json, "$").len(), 1);
+
+    assert_eq!(paths(&json, "$..book[?@.price > 20]"), vec!["$['store']['book'][3]"]);
+    assert_eq!(
+        paths(&json, "$.store.bicycle[\"color\"]"),
+        vec!["$['store']['bicycle']['color']"]
+    );
+    let path: JsonPath = "$.store.book[1].price".parse().unwrap();
+    let nodes = path.query(&json);
+    assert_eq!(nodes[0].to_pointer().to_string(), "/store/book/1/price");
+    assert_eq!(json.get(&nodes[0].to_pointer()).unwrap(), *nodes[0].value());
+    assert_eq!(path.to_string(), "$.store.book[1].price");
+}
+
+#[test]
+fn test_jsonpath_selectors() {
+    let json: Json = r#"["a", "b", "c", "d", "e", "f", "g"]"#.parse().unwrap();
+    let strs =
+        |items: &[&str]| -> Vec<Json> { items.iter().map(|s| Json::new(*s)).collect() };
+
+    assert_eq!(query(&json, "$[1:3]"), strs(&["b", "c"]));
+    assert_eq!(query(&json, "$[5:]"), strs(&["f", "g"]));
+    assert_eq!(query(&json, "$[1:5:2]"), strs(&["b", "d"]));
+    assert_eq!(query(&json, "$[5:1:-2]"), strs(&["f", "d"]));
+    assert_eq!(query(&json, "$[::-1]"), strs(&["g", "f", "e", "d", "c", "b", "a"]));
+    assert_eq!(query(&json, "$[ -2 : ]"), strs(&["f", "g"]));
+    assert_eq!(query(&json, "$[::0]"), strs(&[]));
+    assert_eq!(query(&json, "$[-100:100:3]"), strs(&["a", "d", "g"]));
+    assert_eq!(query(&json, "$[0, 0, -1]"), strs(&["a", "a", "g"]));
+    assert_eq!(query(&json, "$[7]"), strs(&[]));
+    assert_eq!(query(&json, "$.a"), strs(&[]));
+
+    let json: Json =
+        r#"{"o": {"j j": {"k.k": 3}}, "'": {"@": 2}, "é": 1}"#.parse().unwrap();
+    assert_eq!(query(&json, "$.o['j j']['k.k']"), vec![Json::new(3)]);
+    assert_eq!(query(&json, "$.o[\"j j\"][\"k.k\"]"), vec![Json::new(3)]);
+    assert_eq!(query(&json, "$[\"'\"][\"@\"]"), vec![Json::new(2)]);
+    assert_eq!(query(&json, "$['\\u00e9']"), vec![Json::new(1)]);
+    assert_eq!(query(&json, "$.é"), vec![Json::new(1)]);
+    assert_eq!(query(&json, "$ .o ..['k.k']"), vec![Json::new(3)]);
+    assert_eq!(paths(&json, "$[\"'\"]"), vec!["$['\\'']"]);
+    assert_eq!(query(&json, "$[0]"), vec![]);
+
+    // pointer to a node, displayed and parsed back, finds the same value.
+    let json: Json = r#"{"a\\b": [1], "ab": [2]}"#.parse().unwrap();
+    let path: JsonPath = "$['a\\\\b'][0]".parse().unwrap();
+    let nodes = path.query(&json);
+    let ptr: Pointer = nodes[0].to_pointer().to_string().parse().unwrap();
+    assert_eq!(json.get(&ptr).unwrap(), Json::new(1));
+}
+
+#[test]
+fn test_jsonpath_filter() {
+    let text = r#"{
+        "a": [3, 5, 1, 2, 4, 6,
+              {"b": "j"}, {"b": "k"}, {"b": {}}, {"b": "kilo"}, {"b": 1.0}]
+    }"#;
+    let json: Json = text.parse().unwrap();
+    let testcases: Vec<(&str, usize)> = vec![
+        ("$.a[?@.b == 'kilo']", 1),
+        ("$.a[?@.b == 1]", 1),
+        ("$.a[?@ > 3.5]", 3),
+        ("$.a[?@.b]", 5),
+        ("$.a[?!@.b]", 6),
+        ("$.a[?@ < 2 || @.b == 'k']", 2),
+        ("$.a[?@ > 1 && @ < 4]", 2),
+        ("$.a[?!(@ > 1 && @ < 4)]", 9),
+        ("$.a[?@.b > 'j']", 2),
+        ("$.a[?@.b == $.x]", 6),
+        ("$.a[?@.b != 'j']", 10),
+        ("$.a[?@ == null]", 0),
+        ("$.a[?match(@.b, 'k.*')]", 2),
+        ("$.a[?search(@.b, '[jk]')]", 3),
+        ("$.a[?match(@.b, '\\\\p{Ll}+')]", 3),
+        ("$.a[?match(@.b, $.a[6].b)]", 1),
+        ("$.a[?search(@.b, $.a[0])]", 0),
+        ("$.a[?length(@.b) == 4]", 1),
+        ("$.a[?length(@) >= 1]", 5),
+        ("$.a[?count(@.*) == 1]", 5),
+        ("$.a[?value(@..b) == 'j']", 1),
+        ("$[?@[0] == 3]", 1),
+        ("$.a[?@ == -0]", 0),
+        ("$.a[?@ == 6e0]", 1),
+        ("$.a[?true == true]", 11),
+    ];
+    for (path, n) in testcases.iter() {
+        assert_eq!(query(&json, path).len(), *n, "{}", path);
+    }
+
+    let json: Json = r#"[{"a": [1, 2]}, {"a": [1, 2]}, {"a": [2, 1]}]"#.parse().unwrap();
+    assert_eq!(paths(&json, "$[?@.a == $[0].a]"), vec!["$[0]", "$[1]"]);
+}
+
+#[test]
+fn test_jsonpath_invalid() {
+    let testcases = [
+        "",
+        " $",
+        "$ ",
+        "@.a",
+        "$.",
+        "$..",
+        "$.1a",
+        "$[]",
+        "$[01]",
+        "$[-0]",
+        "$[9007199254740992]",
+        "$[1:2:3:4]",
+        "$['a\"]",
+        "$['\\'']extra",
+        "$[\"\\'\"]",
+        "$['\\uD800']",
+        "$[?@.a == 1 ==]",
+        "$[?@.* == 1]",
+        "$[?@..a == 1]",
+        "$[?length(@.*) == 1]",
+        "$[?length(@)]",
+        "$[?count(1) == 1]",
+        "$[?match(@.a) == true]",
+        "$[?match(@.a, 'a') == true]",
+        "$[?match(@.a, '(a')]",
+        "$[?search(@.a, '\\\\p{Xx}')]",
+        "$[?search(@.a, '\\\\d')]",
+        "$[?foo(@.a)]",
+        "$[?1]",
+        "$[?(@.a]",
+        "$[?@.a = 1]",
+        "$[?True]",
+    ];
+    for path in testcases.iter() {
+        match path.parse::<JsonPath>() {
+            Err(Error::JpathFail(_, _)) => (),
+            res => panic!("{:?} {:?}", path, res),
+        }
+    }
+}
@@ -4,7 +4,25 @@ use std::io;
 
 use unicode_reader::CodePoints;
 
-use crate::{json::Json, Error, Result};
+use crate::{error::Location, json::Json, lex::Lex, parse::Parser, Error, Result};
+
+/// Framing enumerates how JSON values are delimited in a stream, refer
+/// [Jsons::framing] and [crate::Writer::framing].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Framing {
+    /// Values are concatenated, optionally separated by whitespace, and
+    /// split by counting brackets. This is the default.
+    Concatenated,
+    /// Newline delimited JSON, each line holds exactly one value. Blank
+    /// lines are ignored, and a line holding more than one value fails
+    /// to parse.
+    Ndjson,
+    /// JSON text sequence as per [RFC-7464](https://tools.ietf.org/html/rfc7464),
+    /// each value is preceded by the record separator `\x1E` and followed
+    /// by a newline. A number, `true`, `false` or `null` that is not
+    /// followed by whitespace is treated as truncated.
+    Rfc7464,
+}
 
 /// Jsons can parse a stream of JSON text supplied by any [Read] instance.
 /// For Example:
@@ -37,13 +55,30 @@ use crate::{json::Json, Error, Result};
 /// }
 /// ```
 ///
+/// To parse JSON text from untrusted parties, configure the [Parser]
+/// with resource limits. If a single JSON value in the stream exceeds
+/// [Parser::max_bytes], iteration fails with [Error::LimitExceeded] and
+/// stops:
+///
+/// ```
+/// use jsondata::{Jsons, Parser};
+///
+/// let text = b"[1, 2] [[[3]]] [4, 5, 6, 7, 8]".as_ref();
+/// let mut iter = Jsons::from(text).parser(Parser::new().max_depth(2).max_bytes(8));
+///
+/// assert!(!iter.next().unwrap().unwrap().is_error());
+/// assert!(iter.next().unwrap().unwrap().is_error()); // max_depth
+/// assert!(iter.next().unwrap().is_err()); // max_bytes
+/// assert!(iter.next().is_none());
+/// ```
+///
 /// [Read]: std::io::Read
 pub struct Jsons<R>
 where
     R: io::Read,
 {
     codes: CodePoints<io::Bytes<R>>,
-    quant: String,
+    splitter: Splitter,
 }
 
 impl<R> From<R> for Jsons<R>
@@ -51,10 +86,115 @@ where
     R: io::Read,
 {
     fn from(input: R) -> Jsons<R> {
-        Jsons {
-            codes: input.into(),
-            quant: String::with_capacity(1024),
-        }
+        Jsons { codes: input.into(), splitter: Splitter::new() }
+    }
+}
+
+impl<R> Jsons<R>
+where
+    R: io::Read,
+{
+    /// Parse each JSON value in the stream using ``parser``, default
+    /// is [Parser::new].
+    pub fn parser(mut self, parser: Parser) -> Jsons<R> {
+        self.splitter.parser = parser;
+        self
+    }
+
+    /// How values are delimited in the stream, default is
+    /// [Framing::Concatenated].
+    ///
+    /// ```
+    /// use jsondata::{Framing, Json, Jsons};
+    ///
+    /// let text = b"1 2\n\n[3]\n".as_ref();
+    /// let values: Vec<Json> = Jsons::from(text).map(|v| v.unwrap()).collect();
+    /// assert_eq!(values.len(), 3);
+    ///
+    /// let iter = Jsons::from(text).framing(Framing::Ndjson);
+    /// let values: Vec<Json> = iter.map(|v| v.unwrap()).collect();
+    /// assert!(values[0].is_error());
+    /// assert_eq!(values[1], Json::new(vec![3]));
+    ///
+    /// let text = b"\x1e1 \x1e[2]\n\x1e3".as_ref();
+    /// let iter = Jsons::from(text).framing(Framing::Rfc7464);
+    /// let values: Vec<Json> = iter.map(|v| v.unwrap()).collect();
+    /// assert_eq!(values[..2], [Json::new(1), Json::new(vec![2])]);
+    /// assert!(values[2].is_error());
+    /// ```
+    pub fn framing(mut self, framing: Framing) -> Jsons<R> {
+        self.splitter.framing = framing;
+        self
+    }
+
+    /// Resynchronize the stream after a malformed value, default is false,
+    /// applicable to [Framing::Concatenated].
+    /// Bracket counting cannot recover from a mismatched `]` or `}`, from
+    /// an unterminated string, or from unclosed brackets, and can swallow
+    /// the rest of the stream into a single value. With resync, such a
+    /// value is yielded as error value, so that a corrupt record in a
+    /// newline delimited stream does not affect the records that follow:
+    ///
+    /// * On a mismatched bracket, the stream is skipped to the next newline.
+    /// * On a newline within a string, the string is taken as unterminated.
+    /// * Within an open array or object, a line starting with a character
+    ///   that can start a value, like `{`, `[`, `"`, a digit, `-`, `t`, `f`
+    ///   or `n`, starts a new value. Hence lines of a value spread across
+    ///   lines shall be indented, or start with a closing bracket.
+    ///
+    /// ```
+    /// use jsondata::{Json, Jsons};
+    ///
+    /// let text = b"{\"a\": [1}\n{\"b\": \"x\n{\"c\": [2,\n[3]\n".as_ref();
+    /// let values: Vec<Json> = Jsons::from(text).resync(true).map(|v| v.unwrap()).collect();
+    /// assert!(values[0].is_error());
+    /// assert!(values[1].is_error());
+    /// assert!(values[2].is_error());
+    /// assert_eq!(values[3], Json::new(vec![3]));
+    /// ```
+    pub fn resync(mut self, resync: bool) -> Jsons<R> {
+        self.splitter.resync = resync;
+        self
+    }
+
+    /// Iterate over JSON values along with the [Location] of their first
+    /// character in the stream. Locations within error values, refer
+    /// [Json::to_error], are also adjusted to the stream.
+    ///
+    /// ```
+    /// use jsondata::Jsons;
+    ///
+    /// let text = b"10\n  [1, 2]\n{}".as_ref();
+    /// let mut iter = Jsons::from(text).located();
+    /// iter.next();
+    /// let (value, at) = iter.next().unwrap().unwrap();
+    /// assert_eq!(value.to_string(), "[1,2]");
+    /// assert_eq!((at.offset(), at.line()), (5, 2));
+    /// ```
+    pub fn located(mut self) -> Located<R> {
+        self.splitter.absolute = true;
+        Located { jsons: self }
+    }
+}
+
+/// Located iterates over JSON values in a stream, along with their
+/// location, refer [Jsons::located].
+pub struct Located<R>
+where
+    R: io::Read,
+{
+    jsons: Jsons<R>,
+}
+
+impl<R> Iterator for Located<R>
+where
+    R: io::Read,
+{
+    type Item = Result<(Json, Location)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.jsons.next()?;
+        Some(item.map(|json| (json, self.jsons.splitter.start.clone())))
     }
 }
 
@@ -65,101 +205,279 @@ where
     type Item = Result<Json>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let mut markers = String::new();
-        let mut ok_ch = self.read_whitespace()?;
+        if self.splitter.closed {
+            return None;
+        }
         loop {
-            let ch = match ok_ch {
-                Ok(ch) => {
-                    //println!("{}", ch);
-                    self.quant.push(ch);
-                    match ch {
-                        '{' => markers.push('}'),
-                        '[' => markers.push(']'),
-                        '}' | ']' => loop {
-                            if let Some(m) = markers.pop() {
-                                if m == ch {
-                                    break;
-                                }
-                            } else if markers.is_empty() {
-                                break;
-                            }
-                        },
-                        '"' => match Jsons::read_string(self)? {
-                            Ok(_) => (),
-                            Err(err) => break Some(Err(err)),
-                        },
-                        _ => (),
+            match self.codes.next() {
+                Some(Ok(ch)) => {
+                    if let Some(item) = self.splitter.push(ch) {
+                        break Some(item);
                     }
-                    //println!("loop {:?} {}", self.quant.as_bytes(), ch);
-                    ch
                 }
-                Err(err) => break Some(Err(err)),
-            };
-            let eov = ch.is_whitespace() || ch == '}' || ch == ']' || ch == '"';
-            if markers.is_empty() && eov {
-                let res = match self.quant.parse() {
-                    Ok(json) => Some(Ok(json)),
-                    Err(s) => Some(Ok(Json::__Error(s))),
-                };
-                //println!("quant {:?} {:?}", self.quant.as_bytes(), res);
-                self.quant.truncate(0);
-                break res;
-            }
-            ok_ch = match self.codes.next() {
-                Some(res) => err_at!(IoError, res),
-                None if !self.quant.is_empty() => {
-                    let res = match self.quant.parse() {
-                        Ok(json) => Some(Ok(json)),
-                        Err(s) => Some(Ok(Json::__Error(s))),
-                    };
-                    //println!("quant {:?} {:?}", self.quant.as_bytes(), res);
-                    self.quant.truncate(0);
-                    break res;
-                }
-                None => break None,
+                Some(Err(err)) => break Some(err_at!(IoError, msg: "{}", err)),
+                None => break self.splitter.finish(),
             }
         }
     }
 }
 
-impl<R> Jsons<R>
-where
-    R: io::Read,
-{
-    fn read_string(&mut self) -> Option<Result<()>> {
-        let mut escape = false;
-        loop {
-            match self.codes.next() {
-                Some(Ok(ch)) if escape => {
-                    self.quant.push(ch);
-                    escape = false;
-                }
-                Some(Ok('\\')) => {
-                    self.quant.push('\\');
-                    escape = true;
+// Splitter splits a stream of characters into JSON values, by counting
+// brackets outside strings, and parses each value. Shared by Jsons and
+// its async variants, so that values are split alike.
+pub(crate) struct Splitter {
+    quant: String,
+    markers: String,
+    string: bool,
+    escape: bool,
+    // location of the next character, and of the current value.
+    pos: Lex,
+    start: Location,
+    // skip till the next newline, after a malformed value.
+    skip: bool,
+    // seen a newline within an open array or object, refer resync.
+    newline: bool,
+    // seen a record separator, refer Framing::Rfc7464.
+    record: bool,
+    pub(crate) parser: Parser,
+    pub(crate) framing: Framing,
+    pub(crate) resync: bool,
+    // report error locations from the start of the stream.
+    pub(crate) absolute: bool,
+    pub(crate) closed: bool,
+}
+
+impl Splitter {
+    pub(crate) fn new() -> Splitter {
+        Splitter {
+            quant: String::with_capacity(1024),
+            markers: String::new(),
+            string: false,
+            escape: false,
+            pos: Lex::new(0, 1, 1),
+            start: Location::new(0, 1, 1),
+            skip: false,
+            newline: false,
+            record: false,
+            parser: Parser::new(),
+            framing: Framing::Concatenated,
+            resync: false,
+            absolute: false,
+            closed: false,
+        }
+    }
+
+    // Push the next character in the stream, and return the JSON value
+    // if it is complete.
+    pub(crate) fn push(&mut self, ch: char) -> Option<Result<Json>> {
+        let at = self.pos.to_location();
+        self.pos.incr_row_col(ch);
+        if self.skip {
+            self.skip = ch != '\n';
+            return None;
+        }
+        match (self.framing, ch) {
+            (Framing::Concatenated, _) => (),
+            (Framing::Ndjson, '\n') => return self.parse_record(),
+            (Framing::Rfc7464, '\u{1e}') => {
+                let res = self.parse_record();
+                self.record = true;
+                return res;
+            }
+            (_, _) => return self.push_record(ch, at),
+        }
+
+        // with resync, a line that starts a new value ends the open value.
+        if self.newline && ch != '\n' {
+            self.newline = false;
+            if starts_value(ch) {
+                let res = self.truncated();
+                // a value that just started cannot be complete.
+                self.push_value(ch, at);
+                return res;
+            }
+        }
+        self.push_value(ch, at)
+    }
+
+    fn push_value(&mut self, ch: char, at: Location) -> Option<Result<Json>> {
+        if self.quant.is_empty() && ch.is_whitespace() {
+            return None; // skip whitespace between values.
+        } else if self.quant.is_empty() {
+            self.start = at;
+        }
+        self.quant.push(ch);
+
+        if self.string {
+            match ch {
+                _ if self.escape => self.escape = false,
+                '\\' => self.escape = true,
+                '"' => self.string = false,
+                '\n' if self.resync => return self.malformed("unterminated string"),
+                _ => {
+                    if let Err(err) = self.check_quant() {
+                        return Some(Err(err));
+                    }
                 }
-                Some(Ok('"')) => {
-                    self.quant.push('"');
-                    break Some(Ok(()));
+            }
+            if self.string || self.escape {
+                return None;
+            }
+        } else {
+            if let Err(err) = self.check_quant() {
+                return Some(Err(err));
+            }
+            match ch {
+                '{' => self.markers.push('}'),
+                '[' => self.markers.push(']'),
+                '}' | ']' if self.resync => match self.markers.pop() {
+                    Some(m) if m == ch => (),
+                    _ => {
+                        self.skip = true;
+                        return self.malformed(&format!("unbalanced {}", ch));
+                    }
+                },
+                '}' | ']' => loop {
+                    if let Some(m) = self.markers.pop() {
+                        if m == ch {
+                            break;
+                        }
+                    } else if self.markers.is_empty() {
+                        break;
+                    }
+                },
+                '"' => {
+                    self.string = true;
+                    return None;
                 }
-                Some(Ok(ch)) => self.quant.push(ch),
-                Some(Err(err)) => break Some(err_at!(IoError, msg: "{}", err)),
-                None => break Some(Ok(())),
+                '\n' if self.resync && !self.markers.is_empty() => self.newline = true,
+                _ => (),
             }
         }
+
+        let eov = ch.is_whitespace() || ch == '}' || ch == ']' || ch == '"';
+        if self.markers.is_empty() && eov {
+            self.parse()
+        } else {
+            None
+        }
     }
 
-    fn read_whitespace(&mut self) -> Option<Result<char>> {
-        loop {
-            match self.codes.next()? {
-                Ok(ch) if !ch.is_whitespace() => break Some(Ok(ch)),
-                Ok(_) => (),
-                res => break Some(err_at!(IoError, res)),
+    // End of stream, return the pending JSON value, if any.
+    pub(crate) fn finish(&mut self) -> Option<Result<Json>> {
+        if self.closed || self.quant.is_empty() {
+            None
+        } else if self.framing == Framing::Concatenated {
+            self.parse()
+        } else {
+            self.parse_record()
+        }
+    }
+
+    fn push_record(&mut self, ch: char, at: Location) -> Option<Result<Json>> {
+        if self.quant.is_empty() && ch.is_whitespace() {
+            return None; // skip whitespace before value.
+        } else if self.quant.is_empty() {
+            self.start = at;
+        }
+        self.quant.push(ch);
+        match self.check_quant() {
+            Ok(()) => None,
+            Err(err) => Some(Err(err)),
+        }
+    }
+
+    // Parse a line, or a record, that shall hold exactly one value.
+    fn parse_record(&mut self) -> Option<Result<Json>> {
+        if self.quant.is_empty() {
+            return None; // blank line, or empty record.
+        } else if self.framing == Framing::Rfc7464 && !self.record {
+            return self.malformed("missing record separator");
+        }
+        let res = match self.parser.parse_single(self.quant.as_bytes()) {
+            Ok(json) if self.framing == Framing::Rfc7464 && self.is_truncated(&json) => {
+                return self.malformed("truncated record");
             }
+            Ok(json) => Ok(json),
+            Err(err) => Ok(Json::__Error(self.locate(err))),
+        };
+        self.reset();
+        Some(res)
+    }
+
+    // Numbers and literals shall be followed by whitespace, refer RFC-7464.
+    fn is_truncated(&self, json: &Json) -> bool {
+        let ws = self.quant.ends_with(char::is_whitespace);
+        let scalar = matches!(
+            json,
+            Json::Null | Json::Bool(_) | Json::Integer(_) | Json::Float(_)
+        );
+        scalar && !ws
+    }
+
+    fn parse(&mut self) -> Option<Result<Json>> {
+        let res = match self.parser.parse(&self.quant) {
+            Ok(json) => Some(Ok(json)),
+            Err(err) => Some(Ok(Json::__Error(self.locate(err)))),
+        };
+        //println!("quant {:?} {:?}", self.quant.as_bytes(), res);
+        self.reset();
+        res
+    }
+
+    // Yield the open value, upto its last line, as error value.
+    fn truncated(&mut self) -> Option<Result<Json>> {
+        let n = self.quant.trim_end().len();
+        self.quant.truncate(n);
+        self.parse() // fails, brackets are not closed.
+    }
+
+    // Yield the value so far as error value, failing at its last character.
+    fn malformed(&mut self, msg: &str) -> Option<Result<Json>> {
+        let mut lex = Lex::new(0, 1, 1);
+        let n = self.quant.chars().count();
+        self.quant.chars().take(n - 1).for_each(|ch| lex.incr_row_col(ch));
+        let res: Result<Json> = err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg);
+        let res = res.or_else(|err| Ok(Json::__Error(self.locate(err))));
+        self.reset();
+        Some(res)
+    }
+
+    fn locate(&self, err: Error) -> Error {
+        match self.absolute {
+            true => err.relocate(&self.start),
+            false => err,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.quant.clear();
+        self.markers.clear();
+        self.string = false;
+        self.escape = false;
+        self.newline = false;
+    }
+
+    // Stop buffering, and iterating, once a JSON value exceeds max_bytes.
+    fn check_quant(&mut self) -> Result<()> {
+        if self.quant.len() > self.parser.max_bytes {
+            let mut lex = Lex::new(0, 1, 1);
+            self.quant.chars().for_each(|ch| lex.incr_row_col(ch));
+            self.quant.clear();
+            self.closed = true;
+            let msg = format!("json value exceeds max_bytes {}", self.parser.max_bytes);
+            let res = err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg);
+            return res.map_err(|err| self.locate(err));
         }
+        Ok(())
     }
 }
 
+// Characters that can start a JSON value, other than whitespace.
+fn starts_value(ch: char) -> bool {
+    matches!(ch, '{' | '[' | '"' | '-' | '0'..='9' | 't' | 'f' | 'n')
+}
+
 #[cfg(test)]
 #[path = "jsons_test.rs"]
 mod jsons_test;
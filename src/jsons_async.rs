@@ -0,0 +1,230 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+use futures_core::Stream;
+
+use crate::{json::Json, jsons::Splitter, parse::Parser, Error, Result};
+
+/// TokioJsons is the async variant of [Jsons](crate::Jsons), that can
+/// parse a stream of JSON text supplied by any [tokio::io::AsyncRead]
+/// instance. Values are split exactly like [Jsons](crate::Jsons), and
+/// yielded as a [Stream] of ``Result<Json, Error>``. Available with
+/// ``tokio`` feature.
+///
+/// ```ignore
+/// use futures::StreamExt;
+///
+/// let file = tokio::fs::File::open("testdata/stream1.jsons").await?;
+/// let mut stream = jsondata::TokioJsons::from(file);
+/// while let Some(json) = stream.next().await {
+///     println!("{:?}", json)
+/// }
+/// ```
+///
+/// Readers that are not [Unpin] can be pinned using [Box::pin].
+#[cfg(feature = "tokio")]
+pub struct TokioJsons<R> {
+    reader: R,
+    decoder: Decoder,
+}
+
+#[cfg(feature = "tokio")]
+impl<R> From<R> for TokioJsons<R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    fn from(reader: R) -> TokioJsons<R> {
+        TokioJsons { reader, decoder: Decoder::new() }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R> TokioJsons<R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    /// Parse each JSON value in the stream using ``parser``, default
+    /// is [Parser::new].
+    pub fn parser(mut self, parser: Parser) -> TokioJsons<R> {
+        self.decoder.splitter.parser = parser;
+        self
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<R> Stream for TokioJsons<R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    type Item = Result<Json>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let reader = &mut this.reader;
+        this.decoder.poll_next(cx, |cx, buf| {
+            let mut buf = tokio::io::ReadBuf::new(buf);
+            match Pin::new(&mut *reader).poll_read(cx, &mut buf) {
+                Poll::Ready(Ok(())) => Poll::Ready(Ok(buf.filled().len())),
+                Poll::Ready(Err(err)) => Poll::Ready(Err(err)),
+                Poll::Pending => Poll::Pending,
+            }
+        })
+    }
+}
+
+/// FuturesJsons is the async variant of [Jsons](crate::Jsons), that can
+/// parse a stream of JSON text supplied by any [futures_io::AsyncRead]
+/// instance. Values are split exactly like [Jsons](crate::Jsons), and
+/// yielded as a [Stream] of ``Result<Json, Error>``. Available with
+/// ``futures-io`` feature.
+///
+/// ```ignore
+/// use futures::StreamExt;
+///
+/// let text = futures::io::Cursor::new(b"[1, 2] {\"a\": 3}".to_vec());
+/// let mut stream = jsondata::FuturesJsons::from(text);
+/// while let Some(json) = stream.next().await {
+///     println!("{:?}", json)
+/// }
+/// ```
+///
+/// Readers that are not [Unpin] can be pinned using [Box::pin].
+#[cfg(feature = "futures-io")]
+pub struct FuturesJsons<R> {
+    reader: R,
+    decoder: Decoder,
+}
+
+#[cfg(feature = "futures-io")]
+impl<R> From<R> for FuturesJsons<R>
+where
+    R: futures_io::AsyncRead + Unpin,
+{
+    fn from(reader: R) -> FuturesJsons<R> {
+        FuturesJsons { reader, decoder: Decoder::new() }
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R> FuturesJsons<R>
+where
+    R: futures_io::AsyncRead + Unpin,
+{
+    /// Parse each JSON value in the stream using ``parser``, default
+    /// is [Parser::new].
+    pub fn parser(mut self, parser: Parser) -> FuturesJsons<R> {
+        self.decoder.splitter.parser = parser;
+        self
+    }
+}
+
+#[cfg(feature = "futures-io")]
+impl<R> Stream for FuturesJsons<R>
+where
+    R: futures_io::AsyncRead + Unpin,
+{
+    type Item = Result<Json>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let reader = &mut this.reader;
+        this.decoder.poll_next(cx, |cx, buf| Pin::new(&mut *reader).poll_read(cx, buf))
+    }
+}
+
+// Decoder decodes UTF-8 text, read in arbitrary chunks, into characters,
+// that are split into JSON values.
+struct Decoder {
+    chunk: Vec<u8>,
+    bytes: Vec<u8>,
+    off: usize,
+    eof: bool,
+    splitter: Splitter,
+}
+
+impl Decoder {
+    fn new() -> Decoder {
+        Decoder {
+            chunk: vec![0; 4096],
+            bytes: vec![],
+            off: 0,
+            eof: false,
+            splitter: Splitter::new(),
+        }
+    }
+
+    fn poll_next<F>(
+        &mut self,
+        cx: &mut Context<'_>,
+        mut read: F,
+    ) -> Poll<Option<Result<Json>>>
+    where
+        F: FnMut(&mut Context<'_>, &mut [u8]) -> Poll<io::Result<usize>>,
+    {
+        loop {
+            if self.splitter.closed {
+                break Poll::Ready(None);
+            }
+            if let Some(item) = self.decode() {
+                break Poll::Ready(Some(item));
+            }
+            if self.eof && self.bytes.is_empty() {
+                break Poll::Ready(self.splitter.finish());
+            } else if self.eof {
+                self.bytes.clear();
+                let res =
+                    err_at!(IoError, msg: "incomplete utf-8 sequence at end of stream");
+                break Poll::Ready(Some(res));
+            }
+            match read(cx, &mut self.chunk) {
+                Poll::Ready(Ok(0)) => self.eof = true,
+                Poll::Ready(Ok(n)) => self.bytes.extend_from_slice(&self.chunk[..n]),
+                Poll::Ready(Err(err)) => {
+                    break Poll::Ready(Some(err_at!(IoError, msg: "{}", err)))
+                }
+                Poll::Pending => break Poll::Pending,
+            }
+        }
+    }
+
+    // Push decoded characters into splitter, until a JSON value is
+    // complete, or an incomplete character is left in the buffer.
+    fn decode(&mut self) -> Option<Result<Json>> {
+        while let Some(b) = self.bytes.get(self.off).copied() {
+            let n = match b {
+                0x00..=0x7F => 1,
+                0xC2..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                0xF0..=0xF4 => 4,
+                _ => 1,
+            };
+            let bs = &self.bytes[self.off..];
+            if bs.len() < n {
+                break;
+            }
+            let ch = match std::str::from_utf8(&bs[..n]) {
+                Ok(s) => s.chars().next()?,
+                Err(err) => {
+                    self.off += 1;
+                    return Some(err_at!(IoError, msg: "{}", err));
+                }
+            };
+            self.off += n;
+            if let Some(item) = self.splitter.push(ch) {
+                return Some(item);
+            }
+        }
+        self.bytes.drain(..self.off);
+        self.off = 0;
+        None
+    }
+}
+
+#[cfg(test)]
+#[path = "jsons_async_test.rs"]
+mod jsons_async_test;
@@ -0,0 +1,78 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::fs;
+
+use futures::StreamExt;
+use tokio::io::AsyncWriteExt;
+
+use super::*;
+use crate::jsons::Jsons;
+
+// Write ``text`` into an in-memory pipe, in chunks of ``size``, and return
+// the reading end.
+fn pipe(text: Vec<u8>, size: usize) -> tokio::io::DuplexStream {
+    let (mut writer, reader) = tokio::io::duplex(16);
+    tokio::spawn(async move {
+        for chunk in text.chunks(size) {
+            writer.write_all(chunk).await.unwrap();
+        }
+    });
+    reader
+}
+
+fn reference(text: &[u8], parser: Parser) -> Vec<Result<Json>> {
+    Jsons::from(text).parser(parser).collect()
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn test_tokio_jsons() {
+    for file in ["stream1.jsons", "stream2.jsons", "stream3.jsons"].iter() {
+        let text = fs::read(format!("testdata/{}", file)).unwrap();
+        let refs = reference(&text, Parser::new());
+        assert!(!refs.is_empty());
+        for size in [1, 2, 3, 7, 64].iter() {
+            let stream = TokioJsons::from(pipe(text.clone(), *size));
+            let items: Vec<Result<Json>> = stream.collect().await;
+            assert_eq!(items, refs, "{} {}", file, size);
+        }
+    }
+
+    let text = b"[1, 2] [[[3]]] [4, 5, 6, 7, 8] 9".to_vec();
+    let parser = Parser::new().max_depth(2).max_bytes(8);
+    let stream = TokioJsons::from(pipe(text.clone(), 3)).parser(parser.clone());
+    let items: Vec<Result<Json>> = stream.collect().await;
+    let refs = reference(&text, parser);
+    assert_eq!(format!("{:?}", items), format!("{:?}", refs));
+    assert_eq!(items.len(), 3);
+    assert!(matches!(items[2], Err(Error::LimitExceeded(_, _, _))));
+
+    let stream = TokioJsons::from(pipe(b"[\"a\xff\"] 1".to_vec(), 2));
+    let items: Vec<Result<Json>> = stream.collect().await;
+    assert!(matches!(items[0], Err(Error::IoError(_, _))));
+
+    let stream = TokioJsons::from(pipe(b"[\"\xc3".to_vec(), 2));
+    let items: Vec<Result<Json>> = stream.collect().await;
+    assert!(matches!(items[0], Err(Error::IoError(_, _))));
+}
+
+#[cfg(feature = "futures-io")]
+#[tokio::test]
+async fn test_futures_jsons() {
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    for file in ["stream1.jsons", "stream2.jsons", "stream3.jsons"].iter() {
+        let text = fs::read(format!("testdata/{}", file)).unwrap();
+        let refs = reference(&text, Parser::new());
+        for size in [1, 5, 64].iter() {
+            let stream = FuturesJsons::from(pipe(text.clone(), *size).compat());
+            let items: Vec<Result<Json>> = stream.collect().await;
+            assert_eq!(items, refs, "{} {}", file, size);
+        }
+    }
+
+    let reader = futures::io::Cursor::new(b" {\"a\": [1]} 2".to_vec());
+    let items: Vec<Result<Json>> = FuturesJsons::from(reader).collect().await;
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[1], Ok(Json::new(2)));
+}
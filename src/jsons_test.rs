@@ -2,7 +2,7 @@
 
 use std::fs::File;
 
-use crate::{json::Json, jsons::Jsons, property::Property};
+use crate::{error::Location, json::Json, jsons::Jsons, property::Property};
 
 #[test]
 fn test_stream0() {
@@ -205,3 +205,150 @@ fn test_stream3() {
     let arr = Json::new::<Vec<Json>>(vec!["hello".into(), obj]);
     assert_eq!(js.next().unwrap().unwrap(), arr);
 }
+
+#[test]
+fn test_stream_limits() {
+    use crate::{parse::Parser, Error};
+
+    let text = b"[1, 2] [[[3]]] 12345 [4, 5, 6, 7, 8] 1".as_ref();
+    let parser = Parser::new().max_depth(2).max_bytes(8).max_digits(4);
+    let mut js = Jsons::from(text).parser(parser);
+
+    assert_eq!(js.next().unwrap().unwrap(), Json::new(vec![1, 2]));
+    match js.next().unwrap().unwrap().to_error() {
+        Some(Error::LimitExceeded(_, _, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    match js.next().unwrap().unwrap().to_error() {
+        Some(Error::LimitExceeded(_, _, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    match js.next().unwrap() {
+        Err(Error::LimitExceeded(_, _, _)) => (),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(js.next().is_none());
+
+    let text = format!("\"{}\"", "a".repeat(100));
+    let mut js = Jsons::from(text.as_bytes()).parser(Parser::new().max_bytes(10));
+    assert!(matches!(js.next().unwrap(), Err(Error::LimitExceeded(_, _, _))));
+    assert!(js.next().is_none());
+}
+
+#[test]
+fn test_stream_resync() {
+    let text = concat!(
+        "{\"a\": 1}\n",
+        "{\"a\": [2}, \"b\": 3}\n",
+        "{\"a\": \"unterminated}\n",
+        "{\"a\": 4} ] {\"a\": 5}\n",
+        "[6, nul]\n",
+        "{\"a\": 7,\n \"b\": \"x\\\ny\"}\n",
+    );
+    let values: Vec<Json> =
+        Jsons::from(text.as_bytes()).resync(true).map(|value| value.unwrap()).collect();
+    assert_eq!(values.len(), 7, "{:?}", values);
+    assert_eq!(values[0].get("/a").unwrap(), Json::new(1));
+    assert!(values[1].is_error());
+    assert!(values[2].is_error());
+    assert_eq!(values[3].get("/a").unwrap(), Json::new(4));
+    assert!(values[4].is_error()); // rest of the line is skipped.
+    assert!(values[5].is_error());
+    assert_eq!(values[6].get("/b").unwrap(), Json::new("xy"));
+
+    // without resync, the unbalanced bracket poisons the stream.
+    let plain: Vec<Json> =
+        Jsons::from(text.as_bytes()).map(|value| value.unwrap()).collect();
+    assert!(!plain.contains(&values[3]));
+    assert!(!plain.contains(&values[6]));
+
+    // a line that starts a value ends a truncated value, same as Ndjson.
+    let text = b"{\"a\": [1, 2\n{\"b\": 1}\n{\"c\": 2}\n[3]\n".as_ref();
+    let values: Vec<Json> =
+        Jsons::from(text).resync(true).map(|value| value.unwrap()).collect();
+    let iter = Jsons::from(text).framing(crate::jsons::Framing::Ndjson);
+    let refs: Vec<Json> = iter.map(|value| value.unwrap()).collect();
+    assert_eq!(values.len(), 4, "{:?}", values);
+    assert!(values[0].is_error());
+    assert_eq!(values[0].to_error(), refs[0].to_error());
+    assert_eq!(values[1..], refs[1..]);
+
+    // indented lines, and lines starting with a closing bracket, continue
+    // the open value.
+    let text = b"{\"a\": [1,\n  2\n],\n\n \"b\": {}\n}\n[3,\n4]".as_ref();
+    let values: Vec<Json> =
+        Jsons::from(text).resync(true).map(|value| value.unwrap()).collect();
+    assert_eq!(values.len(), 3, "{:?}", values);
+    assert_eq!(values[0].get("/a/1").unwrap(), Json::new(2));
+    assert!(values[1].is_error()); // ``4`` starts a line, and a value.
+    assert!(values[2].is_error());
+}
+
+#[test]
+fn test_stream_located() {
+    let text = "1 [2]\n  {\"a\":\n nul}\n\"é\" [3}\n\"x\"";
+    let items: Vec<(Json, Location)> = Jsons::from(text.as_bytes())
+        .resync(true)
+        .located()
+        .map(|item| item.unwrap())
+        .collect();
+    let locs: Vec<(usize, usize, usize)> =
+        items.iter().map(|(_, at)| (at.offset(), at.line(), at.column())).collect();
+    let refv = vec![(0, 1, 1), (2, 1, 3), (8, 2, 3), (20, 4, 1), (25, 4, 6), (29, 5, 1)];
+    assert_eq!(locs, refv);
+    assert_eq!(items[5].0, Json::new("x"));
+
+    let err = items[2].0.to_error().unwrap();
+    let at = err.location().unwrap();
+    assert_eq!((at.offset(), at.line(), at.column()), (15, 3, 2));
+    let err = items[4].0.to_error().unwrap();
+    let at = err.location().unwrap();
+    assert_eq!((at.offset(), at.line(), at.column()), (27, 4, 8));
+}
+
+#[test]
+fn test_stream_framing() {
+    use crate::{jsons::Framing, Error};
+
+    let text = "1 2\n\n  [3,\n4]\r\n\"a\"\"b\"\n{\"c\": 5}";
+    let values: Vec<Json> = Jsons::from(text.as_bytes())
+        .framing(Framing::Ndjson)
+        .map(|value| value.unwrap())
+        .collect();
+    assert_eq!(values.len(), 5);
+    assert!(values[0].is_error());
+    assert!(values[1].is_error());
+    assert!(values[2].is_error());
+    assert!(values[3].is_error());
+    assert_eq!(values[4].get("/c").unwrap(), Json::new(5));
+
+    let items: Vec<(Json, Location)> = Jsons::from(text.as_bytes())
+        .framing(Framing::Ndjson)
+        .located()
+        .map(|item| item.unwrap())
+        .collect();
+    let lines: Vec<usize> = items.iter().map(|(_, at)| at.line()).collect();
+    assert_eq!(lines, vec![1, 3, 4, 5, 6]);
+    let err = items[0].0.to_error().unwrap();
+    assert_eq!(err.location().unwrap().offset(), 2);
+
+    let text = "\x1e{\"a\": 1}\n\x1e\x1e 2 \n\x1etrue\x1e[\n3\n]\n\x1e\"x\"";
+    let values: Vec<Json> = Jsons::from(text.as_bytes())
+        .framing(Framing::Rfc7464)
+        .map(|value| value.unwrap())
+        .collect();
+    assert_eq!(values.len(), 5);
+    assert_eq!(values[0].get("/a").unwrap(), Json::new(1));
+    assert_eq!(values[1], Json::new(2));
+    assert!(values[2].is_error()); // truncated
+    assert_eq!(values[3], Json::new(vec![3]));
+    assert_eq!(values[4], Json::new("x"));
+
+    let mut js = Jsons::from(b"1\n\x1e2\n".as_ref()).framing(Framing::Rfc7464);
+    match js.next().unwrap().unwrap().to_error() {
+        Some(Error::ParseFail(_, msg, _)) => assert!(msg.contains("separator")),
+        res => panic!("unexpected {:?}", res),
+    }
+    assert_eq!(js.next().unwrap().unwrap(), Json::new(2));
+    assert!(js.next().is_none());
+}
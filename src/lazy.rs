@@ -0,0 +1,48 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::sync::OnceLock;
+
+use crate::{error::Location, json::Json, parse::Parser};
+
+// Lazy captures JSON text of an array or an object, that is parsed into
+// Json value on first access, refer Parser::lazy_depth and
+// Parser::lazy_pointer. This extends the deferred conversion of numbers,
+// refer Integral and Floating, to containers.
+#[derive(Clone, Debug)]
+pub struct Lazy {
+    text: String,
+    at: Location,
+    parser: Parser,
+    value: OnceLock<Json>,
+}
+
+impl Lazy {
+    pub(crate) fn new(text: &str, at: Location, parser: Parser) -> Lazy {
+        Lazy {
+            text: text.to_string(),
+            at,
+            parser,
+            value: OnceLock::new(),
+        }
+    }
+
+    // Parse the captured text once, parse failures are returned as
+    // error value.
+    pub(crate) fn as_value(&self) -> &Json {
+        self.value.get_or_init(|| self.parse())
+    }
+
+    pub(crate) fn into_value(mut self) -> Json {
+        match self.value.take() {
+            Some(value) => value,
+            None => self.parse(),
+        }
+    }
+
+    fn parse(&self) -> Json {
+        match self.parser.parse_at(&self.text, &self.at) {
+            Ok(value) => value,
+            Err(err) => Json::__Error(err),
+        }
+    }
+}
@@ -1,16 +1,33 @@
 // Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
 
+use crate::error::{Error, Location};
+
 // This is local type, useful to pinpoint position of lex-failures.
 #[derive(Debug)]
 pub struct Lex {
     pub off: usize,
     pub row: usize,
     pub col: usize,
+    pub depth: usize,
+    // Syntax errors recorded while diagnosing, refer Parser::diagnose.
+    pub errors: Option<Vec<Error>>,
+    // JSON pointers to capture, and path of the value being parsed, refer
+    // Parser::lazy_pointer.
+    pub pointers: Vec<Vec<String>>,
+    pub path: Vec<String>,
 }
 
 impl Lex {
     pub fn new(off: usize, row: usize, col: usize) -> Lex {
-        Lex { off, row, col }
+        Lex {
+            off,
+            row,
+            col,
+            depth: 0,
+            errors: None,
+            pointers: vec![],
+            path: vec![],
+        }
     }
 
     pub fn incr_col(&mut self, i: usize) {
@@ -18,7 +35,28 @@ impl Lex {
         self.col += i;
     }
 
-    pub fn format(&self, prefix: &str) -> String {
-        format!("{} at offset:{} line:{} col:{}", prefix, self.off, self.row, self.col)
+    pub fn incr_row_col(&mut self, ch: char) {
+        self.off += ch.len_utf8();
+        if ch == '\n' {
+            self.row += 1;
+            self.col = 1;
+        } else {
+            self.col += ch.len_utf8();
+        }
+    }
+
+    pub fn skip_bytes(&mut self, bs: &[u8]) {
+        self.off += bs.len();
+        match bs.iter().rposition(|b| *b == b'\n') {
+            Some(n) => {
+                self.row += bs.iter().filter(|b| **b == b'\n').count();
+                self.col = bs.len() - n;
+            }
+            None => self.col += bs.len(),
+        }
+    }
+
+    pub fn to_location(&self) -> Location {
+        Location::new(self.off, self.row, self.col)
     }
 }
@@ -7,11 +7,28 @@
 //!
 //! * Support for 128-bit signed integers.
 //! * Deferred conversion of numbers.
+//! * Deferred parsing of nested arrays and objects, using [`Parser::lazy_depth`].
 //! * Serialization from Rust native type, [`Json`], to JSON text.
+//! * Pretty printing with configurable indentation, using [`Format`].
+//! * Streaming JSON text into [`std::io::Write`], using [`Writer`].
+//! * Canonical JSON text, as per [RFC-8785](https://tools.ietf.org/html/rfc8785).
 //! * De-serialization from JSON text to Rust native [`Json`] type.
-//! * [CRUD] operation on JSON documents, using [JSON Pointer].
+//! * Parse UTF-8 encoded bytes, or [`std::io::Read`], without an intermediate [`String`].
+//! * Strict, JSON5 and lenient parsing profiles, using [`Parser`].
+//! * Zero-copy parsing into borrowed [`JsonRef`] documents.
+//! * [CRUD] operation on JSON documents, using [JSON Pointer], parsed once
+//!   into [`jptr::Pointer`] for repeated lookups.
+//! * Extract values at [JSON Pointer] paths in one pass, using [`jptr::extract`].
+//! * Relative JSON Pointer, resolved against a location, using [`jptr::RelativePointer`].
+//! * Query JSON documents using [JSONPath], refer [`jsonpath::JsonPath`].
 //! * Sorted keys in property object.
-//! * Streaming JSON parser, using [`Jsons`] type.
+//! * Streaming JSON parser, using [`Jsons`] type, and its async variants
+//!   `TokioJsons` and `FuturesJsons`, with `tokio` and `futures-io` features.
+//! * Concatenated, NDJSON and RFC-7464 framing of JSON streams, using [`Framing`].
+//! * Parse NDJSON text on a pool of threads, using [`ParLines`].
+//! * Push parser for JSON text received in chunks, using [`Feeder`] type.
+//! * Event-based pull parser, using [`Tokens`] iterator.
+//! * Resource limits for parsing untrusted JSON text, using [`Parser`].
 //! * Support [JSON5](http://json5.org) standard.
 //! * Common arithmetic and logical ops implemented for [`Json`].
 //! * [`Json`] objects can be compared and sorted.
@@ -34,6 +51,28 @@
 //! assert_eq!(text1, text2);
 //! ```
 //!
+//! To serialise [`Json`] type to human readable JSON text, or to JSON5 text:
+//!
+//! ```
+//! use jsondata::{Format, Json, Mode};
+//!
+//! let json: Json = r#"{"a": [1, 2, 3], "b": null}"#.parse().unwrap();
+//!
+//! let text1 = json.to_pretty_string(); // indent with two spaces
+//! let text2 = json.to_string_with(&Format::new().tabs(true).compact_arrays(true));
+//! let text3 = json.to_string_with(&Format::compact().mode(Mode::Json5));
+//! ```
+//!
+//! To parse JSON text that must strictly conform to RFC-8259:
+//!
+//! ```
+//! use jsondata::{Parser, Profile};
+//!
+//! let parser = Parser::new().profile(Profile::Strict);
+//! assert!(parser.parse(r#"[null,true,false,10,"true"]"#).is_ok());
+//! assert!(parser.parse(r#"[null,true,false,0x10,"true"]"#).is_err());
+//! ```
+//!
 //! When parsing a JSON text to [Json] instance, numbers are not parsed
 //! right away, hence calls to [integer] and [float] methods will have
 //! to compute the value every time,
@@ -49,10 +88,12 @@
 //! }
 //! ```
 //!
-//! If JSON text is going to come from untrusted parties,
+//! If JSON text is going to come from untrusted parties, configure
+//! resource limits while parsing, and validate the parsed value,
 //!
 //! ```
-//! let mut json = r#"{"a": 1000}"#.parse::<jsondata::Json>().unwrap();
+//! let parser = jsondata::Parser::new().max_bytes(1 << 20).max_string(1 << 16);
+//! let mut json = parser.parse(r#"{"a": 1000}"#).unwrap();
 //! match json.validate() { // validate
 //!     Ok(_) => (),
 //!     Err(s) => println!("{:?}", s),
@@ -188,6 +229,7 @@
 //! [JSON]: https://tools.ietf.org/html/rfc8259
 //! [CRUD]: https://en.wikipedia.org/wiki/Create,_read,_update_and_delete
 //! [JSON Pointer]: https://tools.ietf.org/html/rfc6901
+//! [JSONPath]: https://www.rfc-editor.org/rfc/rfc9535
 //! [integer]: enum.Json.html#method.integer
 //! [float]: enum.Json.html#method.float
 
@@ -212,18 +254,41 @@ pub type Result<T> = std::result::Result<T, Error>;
 
 #[macro_use]
 mod error;
+mod canonical;
+mod feeder;
+mod format;
+mod iregexp;
 mod json;
+mod json_ref;
 mod jsons;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod jsons_async;
+mod lazy;
 mod lex;
 mod num;
 mod ops;
+mod par_lines;
 mod parse;
 mod property;
+mod tokens;
+mod writer;
 
 pub mod jptr;
+pub mod jsonpath;
 
 // Re-exports for API documentation.
-pub use error::Error;
+pub use error::{Error, Location};
+pub use feeder::Feeder;
+pub use format::{Format, Mode};
 pub use json::Json;
-pub use jsons::Jsons;
+pub use json_ref::{JsonRef, PropertyRef};
+pub use jsons::{Framing, Jsons, Located};
+#[cfg(feature = "futures-io")]
+pub use jsons_async::FuturesJsons;
+#[cfg(feature = "tokio")]
+pub use jsons_async::TokioJsons;
+pub use par_lines::ParLines;
+pub use parse::{Duplicates, Parser, Profile, DEFAULT_MAX_DEPTH};
 pub use property::Property;
+pub use tokens::{Token, Tokens};
+pub use writer::Writer;
@@ -8,17 +8,19 @@ use crate::{Error, Result};
 fn parse_integer(text: &[u8]) -> Result<i128> {
     use std::str::from_utf8_unchecked;
 
+    let (neg, digits) = match text {
+        [b'-', rest @ ..] => (true, rest),
+        [b'+', rest @ ..] => (false, rest),
+        rest => (false, rest),
+    };
     let res = unsafe {
-        if text.len() > 2 && text[0] == 48 && text[1] == 120
-        // "0x"
-        {
-            i128::from_str_radix(from_utf8_unchecked(&text[2..]), 16)
-        } else if text.len() > 3 && text[0] == 45 && text[1] == 48 && text[2] == 120
-        // "-0x"
-        {
-            i128::from_str_radix(from_utf8_unchecked(&text[3..]), 16).map(|x| -x)
-        } else {
-            from_utf8_unchecked(text).parse::<i128>()
+        match digits {
+            // "0x" or "0X"
+            [b'0', b'x' | b'X', hex @ ..] => {
+                let res = i128::from_str_radix(from_utf8_unchecked(hex), 16);
+                res.map(|x| if neg { -x } else { x })
+            }
+            _ => from_utf8_unchecked(text).parse::<i128>(),
         }
     };
     err_at!(InvalidNumber, res)
@@ -31,6 +33,75 @@ fn parse_float(text: &[u8]) -> Result<f64> {
     err_at!(InvalidNumber, unsafe { from_utf8_unchecked(text).parse::<f64>() })
 }
 
+/// Return whether ``text`` is a number as per the grammar defined by
+/// RFC-8259, that is, `-? (0 | [1-9][0-9]*) (.[0-9]+)? ([eE][+-]?[0-9]+)?`
+pub fn is_json_number(text: &str) -> bool {
+    let digits = |bs: &[u8]| bs.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut bs = text.as_bytes();
+    if let [b'-', rest @ ..] = bs {
+        bs = rest;
+    }
+    match bs {
+        [b'0', rest @ ..] => bs = rest,
+        [b'1'..=b'9', ..] => bs = &bs[digits(bs)..],
+        _ => return false,
+    }
+    if let [b'.', rest @ ..] = bs {
+        match digits(rest) {
+            0 => return false,
+            n => bs = &rest[n..],
+        }
+    }
+    if let [b'e' | b'E', rest @ ..] = bs {
+        let rest = match rest {
+            [b'+' | b'-', rest @ ..] => rest,
+            rest => rest,
+        };
+        match digits(rest) {
+            0 => return false,
+            n => bs = &rest[n..],
+        }
+    }
+    bs.is_empty()
+}
+
+/// Return whether ``text`` is a number as per the grammar defined by
+/// JSON5, excluding Infinity and NaN, that is, an optionally signed,
+/// hexadecimal integer or decimal number with optional leading or
+/// trailing decimal point.
+pub fn is_json5_number(text: &str) -> bool {
+    let digits = |bs: &[u8]| bs.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    let mut bs = text.as_bytes();
+    if let [b'-' | b'+', rest @ ..] = bs {
+        bs = rest;
+    }
+    match bs {
+        [b'0', b'x' | b'X', rest @ ..] => {
+            return !rest.is_empty() && rest.iter().all(|b| b.is_ascii_hexdigit());
+        }
+        [b'0', rest @ ..] => bs = rest,
+        [b'1'..=b'9', ..] => bs = &bs[digits(bs)..],
+        [b'.', rest @ ..] if digits(rest) > 0 => (),
+        _ => return false,
+    }
+    if let [b'.', rest @ ..] = bs {
+        bs = &rest[digits(rest)..];
+    }
+    if let [b'e' | b'E', rest @ ..] = bs {
+        let rest = match rest {
+            [b'+' | b'-', rest @ ..] => rest,
+            rest => rest,
+        };
+        match digits(rest) {
+            0 => return false,
+            n => bs = &rest[n..],
+        }
+    }
+    bs.is_empty()
+}
+
 #[derive(Clone, Debug)]
 pub enum Integral {
     Text { len: usize, bytes: [u8; 128] },
@@ -51,7 +122,7 @@ macro_rules! convert_to_integral {
 
 convert_to_integral! {u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, usize, isize }
 
-impl<'a> TryFrom<&'a str> for Integral {
+impl TryFrom<&str> for Integral {
     type Error = Error;
 
     fn try_from(val: &str) -> Result<Integral> {
@@ -183,7 +254,7 @@ macro_rules! convert_to_float {
 
 convert_to_float! {f32, f64}
 
-impl<'a> TryFrom<&'a str> for Floating {
+impl TryFrom<&str> for Floating {
     type Error = Error;
 
     fn try_from(val: &str) -> Result<Floating> {
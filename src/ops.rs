@@ -19,10 +19,33 @@ macro_rules! check_error {
     }};
 }
 
+// Arrays and objects captured by Parser::lazy_depth are parsed before
+// operating on them, failing with the parse error if their text is invalid.
+macro_rules! resolve_lazy {
+    ($val:ident, $op:ident) => {
+        if $val.is_lazy() {
+            return match $val.into_resolved() {
+                err @ Json::__Error(_) => err,
+                val => val.$op(),
+            };
+        }
+    };
+    ($lhs:ident, $rhs:ident, $op:ident) => {
+        if $lhs.is_lazy() || $rhs.is_lazy() {
+            return match ($lhs.into_resolved(), $rhs.into_resolved()) {
+                (err @ Json::__Error(_), _) | (_, err @ Json::__Error(_)) => err,
+                (lhs, rhs) => lhs.$op(rhs),
+            };
+        }
+    };
+}
+
 impl Add for Json {
     type Output = Json;
 
     fn add(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, add);
+
         use crate::json::Json::{Array, Float, Integer, Null, Object, String as S};
 
         match (&self, &rhs) {
@@ -82,6 +105,8 @@ impl Sub for Json {
     type Output = Json;
 
     fn sub(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, sub);
+
         use crate::json::Json::{Array, Float, Integer, Null, Object};
 
         match (&self, &rhs) {
@@ -139,6 +164,8 @@ impl Mul for Json {
     type Output = Json;
 
     fn mul(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, mul);
+
         use crate::json::Json::{Float, Integer, Null, Object, String as S};
 
         match (&self, &rhs) {
@@ -195,6 +222,8 @@ impl Div for Json {
     type Output = Json;
 
     fn div(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, div);
+
         use crate::json::Json::{Float, Integer, Null, String as S};
 
         match (&self, &rhs) {
@@ -255,6 +284,8 @@ impl Rem for Json {
     type Output = Json;
 
     fn rem(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, rem);
+
         use crate::json::Json::{Float, Integer, Null};
 
         match (&self, &rhs) {
@@ -310,6 +341,8 @@ impl Neg for Json {
     type Output = Json;
 
     fn neg(self) -> Json {
+        resolve_lazy!(self, neg);
+
         match self {
             Json::Null => Json::Null,
             Json::Integer(_) => match self.to_integer_result() {
@@ -332,6 +365,8 @@ impl Shl for Json {
     type Output = Json;
 
     fn shl(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, shl);
+
         match (self.to_integer(), rhs.to_integer()) {
             (Some(l), Some(r)) => Json::new(l << r),
             (_, _) => {
@@ -348,6 +383,8 @@ impl Shr for Json {
     type Output = Json;
 
     fn shr(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, shr);
+
         match (self.to_integer(), rhs.to_integer()) {
             (Some(l), Some(r)) => Json::new(l >> r),
             (_, _) => {
@@ -364,6 +401,8 @@ impl BitAnd for Json {
     type Output = Json;
 
     fn bitand(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, bitand);
+
         use crate::json::Json::Integer;
 
         match (self, rhs) {
@@ -386,6 +425,8 @@ impl BitOr for Json {
     type Output = Json;
 
     fn bitor(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, bitor);
+
         use crate::json::Json::Integer;
 
         match (self, rhs) {
@@ -408,6 +449,8 @@ impl BitXor for Json {
     type Output = Json;
 
     fn bitxor(self, rhs: Json) -> Json {
+        resolve_lazy!(self, rhs, bitxor);
+
         use crate::json::Json::Integer;
 
         match (self, rhs) {
@@ -430,6 +473,8 @@ impl Not for Json {
     type Output = Json;
 
     fn not(self) -> Json {
+        resolve_lazy!(self, not);
+
         if self.is_error() {
             return self;
         }
@@ -459,12 +504,12 @@ impl Index<isize> for Json {
     type Output = Json;
 
     fn index(&self, index: isize) -> &Json {
-        match self {
+        match self.resolve() {
             Json::Array(arr) => match normalized_offset(index, arr.len()) {
                 Some(off) => &arr[off],
                 None => &INDEX_OUT_OF_BOUND,
             },
-            Json::__Error(_) => self,
+            err @ Json::__Error(_) => err,
             _ => &NOT_AN_ARRAY,
         }
     }
@@ -474,7 +519,7 @@ impl Index<&str> for Json {
     type Output = Json;
 
     fn index(&self, index: &str) -> &Json {
-        match self {
+        match self.resolve() {
             Json::Object(obj) => match obj.binary_search_by(|p| p.as_key().cmp(index)) {
                 Ok(off) => obj[off].as_value(),
                 Err(_) => &PROPERTY_NOT_FOUND,
@@ -486,13 +531,14 @@ impl Index<&str> for Json {
                 },
                 Err(_) => &NOT_AN_INDEX,
             },
-            Json::__Error(_) => self,
+            err @ Json::__Error(_) => err,
             _ => &NOT_A_CONTAINER,
         }
     }
 }
 
 pub(crate) fn index_mut<'a>(val: &'a mut Json, key: &str) -> Result<&'a mut Json> {
+    let val = val.resolve_mut();
     match val {
         Json::Object(obj) => match obj.binary_search_by(|p| p.as_key().cmp(key)) {
             Ok(off) => Ok(obj[off].as_mut_value()),
@@ -0,0 +1,321 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::io::{self, Read};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use crate::{error::Location, json::Json, lex::Lex, parse::Parser, Error, Result};
+
+// Chunk of text, aligned to lines, as (sequence number, line number of
+// first line, byte offset of first line, text).
+type Job = (usize, usize, usize, Vec<u8>);
+
+// Values parsed from a chunk, as ([(line number, value)], error that stops
+// the iteration after the values).
+type Parsed = (Vec<(usize, Json)>, Option<Error>);
+
+// Parsed chunk, as (sequence number, parsed values).
+type Done = (usize, Parsed);
+
+/// ParLines parses newline delimited JSON text, refer
+/// [crate::Framing::Ndjson], on a pool of threads. Text supplied by any
+/// [Read] instance, a memory buffer or a file, is split into chunks
+/// aligned to lines, and each chunk is parsed by one of the threads.
+/// Values are yielded in the same order as in the text, along with
+/// their line number, starting from 1.
+///
+/// ```
+/// use jsondata::{Json, ParLines};
+///
+/// let text = b"{\"a\": 1}\n\n[2, 3]\n\"four\"\n".as_ref();
+/// let values: Vec<(usize, Json)> = ParLines::from(text).map(|v| v.unwrap()).collect();
+/// assert_eq!(values[1], (3, Json::new(vec![2, 3])));
+/// assert_eq!(values[2], (4, Json::new("four")));
+/// ```
+///
+/// Same as [crate::Jsons] with [crate::Framing::Ndjson], blank lines are
+/// ignored, and a line that fails to parse, or holds more than one
+/// value, is yielded as error value, refer [Json::is_error], with error
+/// location adjusted to the text. A line longer than [Parser::max_bytes]
+/// is yielded as [Error::LimitExceeded], without reading the rest of the
+/// line, and errors from the [Read] instance are yielded as
+/// [Error::IoError]. In both cases, values from the lines before are
+/// yielded first, and iteration stops after the error.
+///
+/// [Read]: std::io::Read
+pub struct ParLines<R>
+where
+    R: io::Read,
+{
+    reader: R,
+    parser: Parser,
+    threads: usize,
+    chunk_size: usize,
+    // pool, created on first iteration.
+    jobs: Option<mpsc::Sender<Job>>,
+    done: Option<mpsc::Receiver<Done>>,
+    handles: Vec<thread::JoinHandle<()>>,
+    // state of the text that is not yet dispatched.
+    remainder: Vec<u8>,
+    line: usize,
+    offset: usize,
+    eof: bool,
+    // sequence numbers of dispatched and yielded chunks.
+    dispatched: usize,
+    yielded: usize,
+    pending: BTreeMap<usize, Parsed>,
+    values: VecDeque<(usize, Json)>,
+    // error to be yielded after the values from all dispatched chunks.
+    error: Option<Error>,
+}
+
+impl<R> From<R> for ParLines<R>
+where
+    R: io::Read,
+{
+    fn from(reader: R) -> ParLines<R> {
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        ParLines {
+            reader,
+            parser: Parser::new(),
+            threads,
+            chunk_size: 1024 * 1024,
+            jobs: None,
+            done: None,
+            handles: vec![],
+            remainder: vec![],
+            line: 1,
+            offset: 0,
+            eof: false,
+            dispatched: 0,
+            yielded: 0,
+            pending: BTreeMap::new(),
+            values: VecDeque::new(),
+            error: None,
+        }
+    }
+}
+
+impl<R> ParLines<R>
+where
+    R: io::Read,
+{
+    /// Parse each line using ``parser``, default is [Parser::new].
+    pub fn parser(mut self, parser: Parser) -> ParLines<R> {
+        self.parser = parser;
+        self
+    }
+
+    /// Number of threads to parse with, default is the available
+    /// parallelism on the machine.
+    pub fn threads(mut self, threads: usize) -> ParLines<R> {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Approximate size of each chunk in bytes, default is 1MB. Chunks
+    /// are extended to the end of line, hence a chunk can be larger.
+    pub fn chunk_size(mut self, chunk_size: usize) -> ParLines<R> {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    fn start(&mut self) {
+        let (jobs, rx) = mpsc::channel::<Job>();
+        let (tx, done) = mpsc::channel::<Done>();
+        let rx = Arc::new(Mutex::new(rx));
+        for _ in 0..self.threads {
+            let (rx, tx, parser) = (Arc::clone(&rx), tx.clone(), self.parser.clone());
+            self.handles.push(thread::spawn(move || loop {
+                let job = match rx.lock() {
+                    Ok(rx) => rx.recv(),
+                    Err(_) => break,
+                };
+                match job {
+                    Ok((seqno, line, offset, text)) => {
+                        let parsed = parse_lines(&parser, &text, line, offset);
+                        if tx.send((seqno, parsed)).is_err() {
+                            break;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }));
+        }
+        self.jobs = Some(jobs);
+        self.done = Some(done);
+    }
+
+    // Read the next chunk of text, upto the last newline in the chunk. On
+    // read error, complete lines read before the error are returned, and
+    // the error is yielded after them. Once the line that is still open,
+    // less its leading whitespace, exceeds max_bytes, reading stops and the
+    // chunk ends a byte after the limit, for parse_lines to fail on it.
+    fn read_chunk(&mut self) -> Option<Vec<u8>> {
+        let max_bytes = self.parser.max_bytes;
+        let mut text = std::mem::take(&mut self.remainder);
+        // remainder holds no newline, the open line starts at 0, and its
+        // value at the first non-whitespace byte.
+        let (mut open, mut value) = (0, None);
+        let n = loop {
+            let limit = match self.chunk_size.checked_sub(text.len()) {
+                Some(n) if n > 0 => n as u64,
+                _ => self.chunk_size as u64, // line longer than chunk_size
+            };
+            let start = text.len();
+            let res = (&mut self.reader).take(limit).read_to_end(&mut text);
+            // scan only the bytes read now.
+            if let Some(n) = text[start..].iter().rposition(|b| *b == b'\n') {
+                (open, value) = (start + n + 1, None);
+            }
+            if value.is_none() {
+                let from = start.max(open);
+                value = text[from..]
+                    .iter()
+                    .position(|b| !b.is_ascii_whitespace())
+                    .map(|n| from + n);
+            }
+            if let Some(value) = value.filter(|value| text.len() - value > max_bytes) {
+                self.eof = true;
+                break value + max_bytes + 1;
+            }
+            match err_at!(IoError, res) {
+                Ok(0) => {
+                    self.eof = true;
+                    break text.len();
+                }
+                Ok(_) if text.len() >= self.chunk_size && open > 0 => break open,
+                Ok(_) => (),
+                Err(err) => {
+                    (self.eof, self.error) = (true, Some(err));
+                    break open;
+                }
+            }
+        };
+        self.remainder = text.split_off(n);
+        (!text.is_empty()).then_some(text)
+    }
+
+    // Keep every thread busy, with one more chunk in queue.
+    fn dispatch(&mut self) {
+        while !self.eof && self.dispatched - self.yielded < self.threads * 2 {
+            let text = match self.read_chunk() {
+                Some(text) => text,
+                None => break,
+            };
+            let (line, offset) = (self.line, self.offset);
+            self.line += text.iter().filter(|b| **b == b'\n').count();
+            self.offset += text.len();
+            let job = (self.dispatched, line, offset, text);
+            if let Some(jobs) = &self.jobs {
+                if let Err(err) = err_at!(IoError, jobs.send(job)) {
+                    (self.eof, self.error) = (true, Some(err));
+                    break;
+                }
+            }
+            self.dispatched += 1;
+        }
+    }
+
+    fn next_value(&mut self) -> Result<Option<(usize, Json)>> {
+        loop {
+            if let Some(value) = self.values.pop_front() {
+                break Ok(Some(value));
+            }
+            self.dispatch();
+            if self.yielded == self.dispatched {
+                break self.error.take().map_or(Ok(None), Err);
+            }
+            while !self.pending.contains_key(&self.yielded) {
+                let (seqno, parsed) = match &self.done {
+                    Some(done) => err_at!(IoError, done.recv())?,
+                    None => break,
+                };
+                self.pending.insert(seqno, parsed);
+            }
+            if let Some((values, err)) = self.pending.remove(&self.yielded) {
+                self.values.extend(values);
+                if let Some(err) = err {
+                    // skip chunks that follow, and stop after the values.
+                    (self.eof, self.error) = (true, Some(err));
+                    self.pending.clear();
+                    self.dispatched = self.yielded + 1;
+                }
+            }
+            self.yielded += 1;
+        }
+    }
+}
+
+impl<R> Iterator for ParLines<R>
+where
+    R: io::Read,
+{
+    type Item = Result<(usize, Json)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.jobs.is_none() {
+            if self.eof {
+                return None;
+            }
+            self.start();
+        }
+        match self.next_value() {
+            Ok(Some(value)) => Some(Ok(value)),
+            Ok(None) => None,
+            Err(err) => {
+                (self.eof, self.jobs, self.done) = (true, None, None);
+                self.remainder.clear();
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<R> Drop for ParLines<R>
+where
+    R: io::Read,
+{
+    fn drop(&mut self) {
+        // closing the job queue stops the threads.
+        self.jobs.take();
+        self.done.take();
+        for handle in self.handles.drain(..) {
+            handle.join().ok();
+        }
+    }
+}
+
+// Parse lines in text, upto the first line that exceeds max_bytes.
+fn parse_lines(parser: &Parser, text: &[u8], line: usize, offset: usize) -> Parsed {
+    let mut values = vec![];
+    let mut off = offset;
+    for (i, text) in text.split(|b| *b == b'\n').enumerate() {
+        // leading whitespace is skipped, same as Jsons.
+        let ws = text.len() - text.trim_ascii_start().len();
+        let at = Location::new(off + ws, line + i, 1 + ws);
+        off += text.len() + 1;
+        let text = &text[ws..];
+        if text.is_empty() {
+            continue; // blank line
+        } else if text.len() > parser.max_bytes {
+            let mut lex = Lex::new(0, 1, 1);
+            lex.skip_bytes(&text[..=parser.max_bytes]);
+            let msg = format!("json value exceeds max_bytes {}", parser.max_bytes);
+            let at = lex.to_location().relocate(&at);
+            let res: Result<()> = err_at!(LimitExceeded, at: at, msg: "{}", msg);
+            return (values, res.err());
+        }
+        let value = match parser.parse_single(text) {
+            Ok(value) => value,
+            Err(err) => Json::__Error(err.relocate(&at)),
+        };
+        values.push((line + i, value));
+    }
+    (values, None)
+}
+
+#[cfg(test)]
+#[path = "par_lines_test.rs"]
+mod par_lines_test;
@@ -0,0 +1,138 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use super::*;
+use crate::jsons::{Framing, Jsons};
+
+struct Failing<'a>(&'a [u8]);
+
+impl<'a> io::Read for Failing<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.0.is_empty() {
+            return Err(io::Error::other("disk failure"));
+        }
+        let n = buf.len().min(self.0.len());
+        buf[..n].copy_from_slice(&self.0[..n]);
+        self.0 = &self.0[n..];
+        Ok(n)
+    }
+}
+
+fn ndjson(n: usize) -> String {
+    let mut text = String::new();
+    for i in 0..n {
+        match i % 7 {
+            0 => text.push_str(&format!("{{\"id\": {}, \"tags\": [\"a\", \"é\"]}}\n", i)),
+            1 => text.push('\n'),
+            2 => text.push_str(&format!("[{}, {}.5, null]\r\n", i, i)),
+            3 => text.push_str("{\"bad\": nul}\n"),
+            4 => text.push_str("1 2\n"),
+            5 => text.push_str(&format!("  \"line {}\"  \n", i)),
+            _ => text.push_str(&format!("{}\n", i)),
+        }
+    }
+    text
+}
+
+#[test]
+fn test_par_lines() {
+    let text = ndjson(1000) + "[\"no newline\"]";
+    let refs: Vec<(usize, Json)> = Jsons::from(text.as_bytes())
+        .framing(Framing::Ndjson)
+        .located()
+        .map(|item| item.map(|(value, at)| (at.line(), value)).unwrap())
+        .collect();
+    assert_eq!(refs.len(), 1000 - 143 + 1); // 143 blank lines
+
+    for (threads, chunk_size) in [(1, 1), (2, 7), (4, 100), (3, 4096), (8, 1 << 20)] {
+        let iter =
+            ParLines::from(text.as_bytes()).threads(threads).chunk_size(chunk_size);
+        let values: Vec<(usize, Json)> = iter.map(|item| item.unwrap()).collect();
+        assert_eq!(values.len(), refs.len());
+        for ((line, value), (ref_line, ref_value)) in values.iter().zip(refs.iter()) {
+            assert_eq!(line, ref_line, "{} {}", threads, chunk_size);
+            match (value.to_error(), ref_value.to_error()) {
+                (Some(err), Some(ref_err)) => {
+                    let (at, ref_at) =
+                        (err.location().unwrap(), ref_err.location().unwrap());
+                    assert_eq!(at.offset(), ref_at.offset());
+                    assert_eq!(at.line(), ref_at.line());
+                }
+                (None, None) => assert_eq!(value, ref_value),
+                _ => panic!("mismatch at line {}", line),
+            }
+        }
+    }
+
+    let mut iter = ParLines::from(b"".as_ref());
+    assert!(iter.next().is_none());
+    assert!(iter.next().is_none());
+    let mut iter = ParLines::from(b"\n \n".as_ref());
+    assert!(iter.next().is_none());
+}
+
+#[test]
+fn test_par_lines_fail() {
+    // values from lines read before the error are yielded first.
+    let text = ndjson(1000) + "[\"partial\"";
+    let mut refs = vec![];
+    for item in Jsons::from(Failing(text.as_bytes())).framing(Framing::Ndjson) {
+        refs.push(item.is_ok());
+        if !refs[refs.len() - 1] {
+            break;
+        }
+    }
+    assert_eq!(refs.len(), 1000 - 143 + 1);
+    for (threads, chunk_size) in [(1, 1), (2, 64), (4, 1000), (8, 1 << 20)] {
+        let iter = ParLines::from(Failing(text.as_bytes()))
+            .threads(threads)
+            .chunk_size(chunk_size);
+        let items: Vec<Result<(usize, Json)>> = iter.collect();
+        assert_eq!(items.len(), refs.len(), "{} {}", threads, chunk_size);
+        assert!(matches!(items.last(), Some(Err(Error::IoError(_, _)))));
+        assert_eq!(items.iter().filter(|item| item.is_err()).count(), 1);
+    }
+
+    // a line exceeding max_bytes stops the iteration, same as Jsons.
+    let parser = Parser::new().max_bytes(10);
+    let text = b"[1, 2]\n  [1, 2, 3, 4, 5]\n3\n".as_ref();
+    let mut iter = Jsons::from(text).framing(Framing::Ndjson).parser(parser.clone());
+    assert_eq!(iter.next().unwrap().unwrap(), Json::new(vec![1, 2]));
+    assert!(matches!(iter.next(), Some(Err(Error::LimitExceeded(_, _, _)))));
+    assert!(iter.next().is_none());
+
+    let mut iter = ParLines::from(text).parser(parser.clone());
+    assert_eq!(iter.next().unwrap().unwrap(), (1, Json::new(vec![1, 2])));
+    match iter.next() {
+        Some(Err(Error::LimitExceeded(_, _, at))) => {
+            assert_eq!((at.offset(), at.line(), at.column()), (20, 2, 14))
+        }
+        res => panic!("unexpected {:?}", res),
+    }
+    assert!(iter.next().is_none());
+
+    let text = ndjson(1000) + &"1".repeat(100) + "\n" + &ndjson(1000);
+    let parser = Parser::new().max_bytes(50);
+    for (threads, chunk_size) in [(1, 1), (2, 64), (4, 1000), (8, 1 << 20)] {
+        let iter = ParLines::from(text.as_bytes())
+            .parser(parser.clone())
+            .threads(threads)
+            .chunk_size(chunk_size);
+        let items: Vec<Result<(usize, Json)>> = iter.collect();
+        assert_eq!(items.len(), 1000 - 143 + 1, "{} {}", threads, chunk_size);
+        assert!(matches!(items.last(), Some(Err(Error::LimitExceeded(_, _, _)))));
+    }
+    // an endless line, without newline, stops once it exceeds max_bytes.
+    let parser = Parser::new().max_bytes(100);
+    for chunk_size in [1, 1024, 1 << 20] {
+        let text = b"[1]\n".chain(io::repeat(b' ').take(10)).chain(io::repeat(b'1'));
+        let mut iter = ParLines::from(text).parser(parser.clone()).chunk_size(chunk_size);
+        assert_eq!(iter.next().unwrap().unwrap(), (1, Json::new(vec![1])));
+        match iter.next() {
+            Some(Err(Error::LimitExceeded(_, _, at))) => {
+                assert_eq!((at.offset(), at.line(), at.column()), (115, 2, 112))
+            }
+            res => panic!("unexpected {:?}", res),
+        }
+        assert!(iter.next().is_none());
+    }
+}
@@ -1,178 +1,676 @@
 // Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
 
-use std::str::CharIndices;
-use std::{char, f64};
+use std::io::{self, Read};
+use std::{borrow::Cow, char, f64};
 
-use lazy_static::lazy_static;
+use unicode_id::UnicodeID;
 
-use crate::property::{self, Property};
-use crate::{json::Json, lex::Lex, num, Error, Result};
+use crate::num::{Floating, Integral};
+use crate::tokens::Tokens;
+use crate::{error::Location, json::Json, json_ref::JsonRef, lazy::Lazy, lex::Lex};
+use crate::{jptr, num, property::Property, Error, Result};
 
-pub fn parse_value(text: &str, lex: &mut Lex) -> Result<Json> {
-    parse_whitespace(text, lex);
+/// Profile enumerates the grammars that [Parser] can accept.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Profile {
+    /// Accept only JSON text as per [RFC-8259](https://tools.ietf.org/html/rfc8259).
+    Strict,
+    /// Accept JSON text as per [JSON5](https://json5.org) specification.
+    Json5,
+    /// Accept JSON5 text, and be forgiving about leading zeros in numbers,
+    /// missing commas and trailing text after the value. This is the
+    /// default profile, used by [str::parse].
+    Lenient,
+}
+
+/// Duplicates enumerates how [Parser] shall handle an object with
+/// repeated property keys, like `{"a": 1, "a": 2}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Duplicates {
+    /// Keep the value of the last occurrence, this is the default.
+    LastWins,
+    /// Keep the value of the first occurrence.
+    FirstWins,
+    /// Fail with [Error::ParseFail], pointing to the second occurrence.
+    Error,
+    /// Collect values of all occurrences, in text order, into an array.
+    Collect,
+}
+
+/// Parser type configures how JSON text is parsed into [Json] value.
+///
+/// ```
+/// use jsondata::{Json, Parser, Profile};
+///
+/// let parser = Parser::new().profile(Profile::Strict);
+/// assert!(parser.parse(r#"{"a": [1, 2.5e3, null]}"#).is_ok());
+/// assert!(parser.parse(r#"{a: [0x10, 00]}"#).is_err());
+///
+/// let parser = Parser::new().profile(Profile::Json5);
+/// assert!(parser.parse(r#"{a: [0x10, .5, Infinity,]}"#).is_ok());
+/// ```
+///
+/// Default configuration uses [Profile::Lenient], which is the same
+/// as parsing text using [str::parse].
+///
+/// To parse JSON text from untrusted parties, configure resource limits.
+/// Text exceeding any of the limits fails with [Error::LimitExceeded]:
+///
+/// ```
+/// use jsondata::{Error, Parser};
+///
+/// let parser = Parser::new().max_depth(2).max_members(3);
+/// assert!(parser.parse("[[1, 2, 3]]").is_ok());
+/// assert!(matches!(parser.parse("[[[1]]]"), Err(Error::LimitExceeded(_, _, _))));
+/// assert!(matches!(parser.parse("[1, 2, 3, 4]"), Err(Error::LimitExceeded(_, _, _))));
+/// ```
+///
+/// Except for nesting depth, which defaults to [DEFAULT_MAX_DEPTH], all
+/// limits are unbounded by default.
+#[derive(Clone, Debug)]
+pub struct Parser {
+    pub(crate) profile: Profile,
+    duplicates: Duplicates,
+    max_depth: usize,
+    pub(crate) max_bytes: usize,
+    max_string: usize,
+    max_members: usize,
+    max_digits: usize,
+    lazy_depth: usize,
+    lazy_pointers: Vec<String>,
+}
+
+/// Default limit on the nesting depth of arrays and objects, refer
+/// [Parser::max_depth].
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser {
+            profile: Profile::Lenient,
+            duplicates: Duplicates::LastWins,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_bytes: usize::MAX,
+            max_string: usize::MAX,
+            max_members: usize::MAX,
+            max_digits: usize::MAX,
+            lazy_depth: usize::MAX,
+            lazy_pointers: vec![],
+        }
+    }
+}
+
+/// Following methods can be chained to configure the parser.
+impl Parser {
+    /// Create a new parser with default configuration.
+    pub fn new() -> Parser {
+        Parser::default()
+    }
+
+    /// Grammar to accept, default is [Profile::Lenient].
+    pub fn profile(mut self, profile: Profile) -> Parser {
+        self.profile = profile;
+        self
+    }
+
+    /// How to handle repeated property keys within an object, default
+    /// is [Duplicates::LastWins].
+    ///
+    /// ```
+    /// use jsondata::{Duplicates, Json, Parser};
+    ///
+    /// let text = r#"{"a": 1, "a": 2}"#;
+    /// let parser = Parser::new().duplicates(Duplicates::FirstWins);
+    /// assert_eq!(parser.parse(text).unwrap().get("/a").unwrap(), Json::new(1));
+    ///
+    /// let parser = Parser::new().duplicates(Duplicates::Collect);
+    /// assert_eq!(parser.parse(text).unwrap().get("/a").unwrap(), Json::new(vec![1, 2]));
+    ///
+    /// let parser = Parser::new().duplicates(Duplicates::Error);
+    /// assert!(parser.parse(text).is_err());
+    /// ```
+    pub fn duplicates(mut self, duplicates: Duplicates) -> Parser {
+        self.duplicates = duplicates;
+        self
+    }
+
+    /// Maximum nesting depth of arrays and objects, default is
+    /// [DEFAULT_MAX_DEPTH]. Since arrays and objects are parsed
+    /// recursively, an unbounded depth can overflow the stack.
+    pub fn max_depth(mut self, max_depth: usize) -> Parser {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// Maximum size of JSON text in bytes. When iterating with
+    /// [crate::Jsons], this applies to each JSON value in the stream.
+    pub fn max_bytes(mut self, max_bytes: usize) -> Parser {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Maximum size of a string value or property key, in bytes, after
+    /// unescaping.
+    pub fn max_string(mut self, max_string: usize) -> Parser {
+        self.max_string = max_string;
+        self
+    }
+
+    /// Maximum number of items in an array, or properties in an object.
+    pub fn max_members(mut self, max_members: usize) -> Parser {
+        self.max_members = max_members;
+        self
+    }
+
+    /// Maximum length of a number, in bytes, including sign, decimal
+    /// point and exponent.
+    pub fn max_digits(mut self, max_digits: usize) -> Parser {
+        self.max_digits = max_digits;
+        self
+    }
+
+    /// Arrays and objects nested deeper than ``lazy_depth`` are not
+    /// parsed, instead their text is captured, after matching the
+    /// brackets, and parsed on first access. Syntax errors within the
+    /// captured text are reported when it is accessed. Default is to
+    /// parse the entire text.
+    ///
+    /// ```
+    /// use jsondata::{Json, Parser};
+    ///
+    /// let parser = Parser::new().lazy_depth(1);
+    /// let text = r#"{"id": 10, "blob": {"a": [1, 2]}, "bad": [1,, 2]}"#;
+    /// let value = parser.parse(text).unwrap();
+    /// assert_eq!(value.get("/id").unwrap(), Json::new(10));
+    /// assert_eq!(value.get("/blob/a/1").unwrap(), Json::new(2));
+    /// assert!(value.get("/bad/0").is_err());
+    /// ```
+    ///
+    /// Captured values are materialized on access through JSON Pointer,
+    /// index, accessor and conversion methods, and when compared,
+    /// serialized or computed. Values that are matched directly against
+    /// [Json] variants shall first be materialized with [Json::compute].
+    pub fn lazy_depth(mut self, lazy_depth: usize) -> Parser {
+        self.lazy_depth = lazy_depth;
+        self
+    }
+
+    /// Capture the array or object at JSON ``pointer``, like
+    /// [Parser::lazy_depth], and parse it on first access. Can be
+    /// called more than once to capture several values. Parsing fails
+    /// with [Error::JptrFail] if pointer is invalid.
+    pub fn lazy_pointer(mut self, pointer: &str) -> Parser {
+        self.lazy_pointers.push(pointer.to_string());
+        self
+    }
+}
+
+impl Parser {
+    /// Parse ``text`` into [Json] value.
+    pub fn parse(&self, text: &str) -> Result<Json> {
+        self.parse_text(text.as_bytes(), &mut Lex::new(0, 1, 1))
+    }
+
+    /// Parse ``text`` into [JsonRef] value, borrowing strings, property
+    /// keys and number lexemes from ``text``. Only strings and keys with
+    /// escape sequences are allocated. Since borrowing is cheap, values
+    /// are not captured for lazy parsing, refer [Parser::lazy_depth].
+    ///
+    /// ```
+    /// use jsondata::{JsonRef, Parser};
+    ///
+    /// let text = r#"{"name": "Joe", "age": 42, "note": "a\tb"}"#;
+    /// let value = Parser::new().parse_ref(text).unwrap();
+    /// assert_eq!(value["name"].as_str(), Some("Joe"));
+    /// assert_eq!(value["age"], JsonRef::Integer("42"));
+    /// assert_eq!(value.get("/note").unwrap().as_str(), Some("a\tb"));
+    /// ```
+    pub fn parse_ref<'a>(&self, text: &'a str) -> Result<JsonRef<'a>> {
+        self.parse_text(text.as_bytes(), &mut Lex::new(0, 1, 1))
+    }
+
+    /// Iterate over JSON ``text``, as string or UTF-8 encoded bytes, as
+    /// a sequence of [Token](crate::Token) events, refer [Tokens].
+    pub fn tokens<'a, T>(&self, text: &'a T) -> Tokens<'a>
+    where
+        T: AsRef<[u8]> + ?Sized,
+    {
+        Tokens::new(text.as_ref(), self.clone())
+    }
+
+    /// Parse UTF-8 encoded ``text`` into [Json] value. Unlike [str::from_utf8],
+    /// encoding is validated only within string values, property keys and
+    /// JSON5 whitespace, invalid encoding fails with [Error::ParseFail] at
+    /// the offending byte.
+    ///
+    /// ```
+    /// use jsondata::Parser;
+    ///
+    /// let value = Parser::new().parse_slice(b"{\"a\": \"\xc3\xa9\"}").unwrap();
+    /// assert_eq!(value.get("/a").unwrap(), jsondata::Json::new("é"));
+    ///
+    /// let err = Parser::new().parse_slice(b"[\"a\xff\"]").unwrap_err();
+    /// assert_eq!(err.location().unwrap().offset(), 3);
+    /// ```
+    pub fn parse_slice(&self, text: &[u8]) -> Result<Json> {
+        self.parse_text(text, &mut Lex::new(0, 1, 1))
+    }
+
+    /// Read UTF-8 encoded text from ``reader`` until end of stream, and parse
+    /// it into [Json] value. Reading stops with [Error::LimitExceeded] if the
+    /// text exceeds [Parser::max_bytes].
+    pub fn parse_reader<R>(&self, reader: R) -> Result<Json>
+    where
+        R: io::Read,
+    {
+        let limit = u64::try_from(self.max_bytes).unwrap_or(u64::MAX);
+        let mut text = vec![];
+        err_at!(IoError, reader.take(limit.saturating_add(1)).read_to_end(&mut text))?;
+        self.parse_slice(&text)
+    }
+
+    /// Parse ``text`` into [Json] value, recovering from syntax errors at
+    /// `,`, `]` and `}` boundaries, so that all syntax errors in the text
+    /// are reported in one pass. Returns the best-effort value, skipping
+    /// array items and object properties that failed to parse, along
+    /// with the errors in text order. Failures other than
+    /// [Error::ParseFail], like [Error::LimitExceeded], abort the parse.
+    ///
+    /// ```
+    /// use jsondata::{Json, Parser, Profile};
+    ///
+    /// let parser = Parser::new().profile(Profile::Json5);
+    /// let (value, errors) = parser.diagnose("{a: [1, x, 3], b: nul, c: 4}");
+    /// assert_eq!(value, "{a: [1, 3], c: 4}".parse::<Json>().unwrap());
+    /// assert_eq!(errors.len(), 2);
+    /// assert_eq!(errors[0].location().unwrap().offset(), 8);
+    /// assert_eq!(errors[1].location().unwrap().offset(), 18);
+    /// ```
+    pub fn diagnose(&self, text: &str) -> (Json, Vec<Error>) {
+        let mut lex = Lex::new(0, 1, 1);
+        lex.errors = Some(vec![]);
+        let res = self.parse_text(text.as_bytes(), &mut lex);
+        let mut errors = lex.errors.take().unwrap_or_default();
+        match res {
+            Ok(json) => (json, errors),
+            Err(err) => {
+                errors.push(err);
+                (Json::Null, errors)
+            }
+        }
+    }
+
+    // Parse text captured at ``at``, refer Parser::lazy_depth.
+    pub(crate) fn parse_at(&self, text: &str, at: &Location) -> Result<Json> {
+        let res = self.parse_text(text.as_bytes(), &mut Lex::new(0, 1, 1));
+        res.map_err(|err| err.relocate(at))
+    }
+
+    // Parser for text captured at ``depth``, with remaining depth and
+    // without capturing nested values.
+    fn materializer(&self, depth: usize) -> Parser {
+        Parser {
+            max_depth: self.max_depth.saturating_sub(depth),
+            lazy_depth: usize::MAX,
+            lazy_pointers: vec![],
+            ..*self
+        }
+    }
+
+    fn parse_text<'a, V: Node<'a>>(&self, text: &'a [u8], lex: &mut Lex) -> Result<V> {
+        if text.len() > self.max_bytes {
+            let msg = format!("text exceeds max_bytes {}", self.max_bytes);
+            err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
+        }
+        for pointer in self.lazy_pointers.iter() {
+            lex.pointers.push(jptr::fragments_of(pointer)?);
+        }
+        let json = parse_value(text, lex, self)?;
+
+        if let Err(err) = self.parse_trailing(text, lex) {
+            record(lex, err)?;
+        }
+        Ok(json)
+    }
+
+    // Parse ``text`` holding a single JSON value, trailing text is not
+    // allowed even with lenient profile, refer jsons::Framing.
+    pub(crate) fn parse_single(&self, text: &[u8]) -> Result<Json> {
+        let mut lex = Lex::new(0, 1, 1);
+        let json = self.parse_text(text, &mut lex)?;
+        self.check_trailing(text, &mut lex)?;
+        Ok(json)
+    }
+
+    // Text after the value can only be whitespace, unless profile is lenient.
+    pub(crate) fn parse_trailing(&self, text: &[u8], lex: &mut Lex) -> Result<()> {
+        if self.profile != Profile::Lenient {
+            self.check_trailing(text, lex)?;
+        }
+        Ok(())
+    }
+
+    fn check_trailing(&self, text: &[u8], lex: &mut Lex) -> Result<()> {
+        parse_whitespace(text, lex, self)?;
+        if lex.off < text.len() {
+            let at = expected(text, lex, "end of text");
+            err_at!(ParseFail, at: at, msg: "unexpected trailing text")?;
+        }
+        Ok(())
+    }
+}
+
+/// Node is implemented by value types that [Parser] can build, parsing
+/// functions are generic over it, so that [Json] and [JsonRef] share the
+/// same grammar.
+pub(crate) trait Node<'a>: Sized {
+    type Property;
+
+    fn null() -> Self;
+
+    fn bool(value: bool) -> Self;
+
+    fn integer(lexeme: &'a str) -> Result<Self>;
+
+    fn float(lexeme: &'a str) -> Result<Self>;
+
+    /// JSON5 Infinity and NaN.
+    fn non_finite(lexeme: &'a str, value: f64) -> Self;
+
+    fn string(value: Cow<'a, str>) -> Self;
+
+    fn array(items: Vec<Self>) -> Self;
+
+    fn as_array_mut(&mut self) -> Option<&mut Vec<Self>>;
+
+    fn object(properties: Vec<Self::Property>) -> Self;
+
+    fn property(key: Cow<'a, str>, value: Self) -> Self::Property;
+
+    fn key(property: &Self::Property) -> &str;
+
+    fn value_mut(property: &mut Self::Property) -> &mut Self;
+
+    /// Array or object text, captured at ``depth``, to be parsed on
+    /// first access. Return None to parse it right away.
+    fn lazy(text: &'a str, at: Location, p: &Parser, depth: usize) -> Option<Self>;
+}
+
+impl<'a> Node<'a> for Json {
+    type Property = Property;
+
+    fn null() -> Json {
+        Json::Null
+    }
+
+    fn bool(value: bool) -> Json {
+        Json::Bool(value)
+    }
+
+    fn integer(lexeme: &'a str) -> Result<Json> {
+        Ok(Json::Integer(Integral::try_from(lexeme)?))
+    }
+
+    fn float(lexeme: &'a str) -> Result<Json> {
+        Ok(Json::Float(Floating::try_from(lexeme)?))
+    }
+
+    fn non_finite(_lexeme: &'a str, value: f64) -> Json {
+        Json::new(value)
+    }
+
+    fn string(value: Cow<'a, str>) -> Json {
+        Json::String(value.into_owned())
+    }
+
+    fn array(items: Vec<Json>) -> Json {
+        Json::Array(items)
+    }
+
+    fn as_array_mut(&mut self) -> Option<&mut Vec<Json>> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    fn object(properties: Vec<Property>) -> Json {
+        Json::Object(properties)
+    }
+
+    fn property(key: Cow<'a, str>, value: Json) -> Property {
+        Property::new(key.into_owned(), value)
+    }
+
+    fn key(property: &Property) -> &str {
+        property.as_key()
+    }
+
+    fn value_mut(property: &mut Property) -> &mut Json {
+        property.as_mut_value()
+    }
+
+    fn lazy(text: &'a str, at: Location, p: &Parser, depth: usize) -> Option<Json> {
+        let lazy = Lazy::new(text, at, p.materializer(depth));
+        Some(Json::__Lazy(Box::new(lazy)))
+    }
+}
+
+pub fn parse_value<'a, V: Node<'a>>(
+    text: &'a [u8],
+    lex: &mut Lex,
+    p: &Parser,
+) -> Result<V> {
+    parse_whitespace(text, lex, p)?;
 
     not_eof(text, lex)?;
 
-    //println!("text -- {:?}", &text[lex.off..].as_bytes());
-    let bs = text[lex.off..].as_bytes();
+    //println!("text -- {:?}", &text[lex.off..]);
+    let json5 = p.profile != Profile::Strict;
+    let bs = &text[lex.off..];
     match bs[0] {
         b'n' => parse_null(text, lex),
         b't' => parse_true(text, lex),
         b'f' => parse_false(text, lex),
-        b'-' if bs.len() > 1 && bs[1] == b'I' => parse_json5_float(text, lex, 1),
-        b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E' => parse_num(text, lex),
-        b'"' => parse_string(text, lex),
-        b'[' => parse_array(text, lex),
-        b'{' => parse_object(text, lex),
-        b'I' => parse_json5_float(text, lex, 2),
-        b'N' => parse_json5_float(text, lex, 3),
+        b'-' | b'+' if json5 && bs.len() > 1 && matches!(bs[1], b'I' | b'N') => {
+            parse_json5_float(text, lex)
+        }
+        b'0'..=b'9' | b'+' | b'-' | b'.' | b'e' | b'E' => parse_num(text, lex, p),
+        b'"' => parse_string(text, lex, p).map(V::string),
+        b'\'' if json5 => parse_string(text, lex, p).map(V::string),
+        b'[' | b'{' if is_lazy(lex, p) => parse_lazy(text, lex, p),
+        b'[' => parse_array(text, lex, p),
+        b'{' => parse_object(text, lex, p),
+        b'I' | b'N' if json5 => parse_json5_float(text, lex),
         ch => {
-            err_at!(ParseFail, msg: "{}", lex.format(&format!("invalid token {}", ch)))
+            err_at!(ParseFail, at: expected(text, lex, "value"), msg: "invalid token {}", ch)
         }
     }
     //println!("valu -- {:?}", v);
 }
 
 #[inline]
-fn parse_null(text: &str, lex: &mut Lex) -> Result<Json> {
-    let text = &text[lex.off..];
-    if text.len() >= 4 && &text[..4] == "null" {
+fn parse_null<'a, V: Node<'a>>(text: &[u8], lex: &mut Lex) -> Result<V> {
+    if text[lex.off..].starts_with(b"null") {
         lex.incr_col(4);
-        Ok(Json::Null)
+        Ok(V::null())
     } else {
-        err_at!(ParseFail, msg: "{}", lex.format("expected null"))
+        err_at!(ParseFail, at: expected(text, lex, "null"), msg: "expected null")
     }
 }
 
 #[inline]
-fn parse_true(text: &str, lex: &mut Lex) -> Result<Json> {
-    let text = &text[lex.off..];
-    if text.len() >= 4 && &text[..4] == "true" {
+fn parse_true<'a, V: Node<'a>>(text: &[u8], lex: &mut Lex) -> Result<V> {
+    if text[lex.off..].starts_with(b"true") {
         lex.incr_col(4);
-        Ok(Json::Bool(true))
+        Ok(V::bool(true))
     } else {
-        err_at!(ParseFail, msg: "{}", lex.format("expected true"))
+        err_at!(ParseFail, at: expected(text, lex, "true"), msg: "expected true")
     }
 }
 
 #[inline]
-fn parse_false(text: &str, lex: &mut Lex) -> Result<Json> {
-    let text = &text[lex.off..];
-    if text.len() >= 5 && &text[..5] == "false" {
+fn parse_false<'a, V: Node<'a>>(text: &[u8], lex: &mut Lex) -> Result<V> {
+    if text[lex.off..].starts_with(b"false") {
         lex.incr_col(5);
-        Ok(Json::Bool(false))
+        Ok(V::bool(false))
     } else {
-        err_at!(ParseFail, msg: "{}", lex.format("expected false"))
+        err_at!(ParseFail, at: expected(text, lex, "false"), msg: "expected false")
     }
 }
 
-fn parse_num(text: &str, lex: &mut Lex) -> Result<Json> {
+fn parse_num<'a, V: Node<'a>>(text: &'a [u8], lex: &mut Lex, p: &Parser) -> Result<V> {
     let text = &text[lex.off..];
 
-    let mut dofn = |t: &str, i: usize, is_float: bool, is_hex: bool| -> Result<Json> {
+    let mut dofn = |t: &'a str, i: usize, is_float: bool, is_hex: bool| -> Result<V> {
+        if t.len() > p.max_digits {
+            let msg = format!("number exceeds max_digits {}", p.max_digits);
+            err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
+        }
+        let ok = match p.profile {
+            Profile::Strict => num::is_json_number(t),
+            Profile::Json5 => num::is_json5_number(t),
+            Profile::Lenient => true,
+        };
+        if !ok {
+            err_at!(ParseFail, at: lex.to_location(), msg: "invalid number {}", t)?;
+        }
         lex.incr_col(i);
         //println!("parse_num -- {}", t);
         if is_float && !is_hex {
-            Ok(Json::Float(num::Floating::try_from(t)?))
+            V::float(t)
         } else {
-            Ok(Json::Integer(num::Integral::try_from(t)?))
+            V::integer(t)
         }
     };
 
     let (mut is_float, mut is_hex) = (false, false);
-    for (i, ch) in text.char_indices() {
-        let mut ok = (ch as u32) > (ISNUMBER.len() as u32);
-        ok = ok || ISNUMBER[ch as usize] == 0;
-        if ok {
-            return dofn(&text[..i], i, is_float, is_hex);
-        } else if !is_float && ISNUMBER[ch as usize] == 2 {
-            is_float = true
-        } else if ISNUMBER[ch as usize] == 3 {
-            is_hex = true
-        }
-    }
-    dofn(text, text.len(), is_float, is_hex)
-}
-
-fn parse_json5_float(txt: &str, lex: &mut Lex, w: usize) -> Result<Json> {
-    lazy_static! {
-        static ref JSON5_FLOAT_LOOKUP: Vec<(String, usize, Json)> = vec![
-            ("".to_string(), 0_usize, Json::Null),
-            ("-Infinity".to_string(), 9, Json::new(f64::NEG_INFINITY)),
-            ("Infinity".to_string(), 8, Json::new(f64::INFINITY)),
-            ("NaN".to_string(), 3, Json::new(f64::NAN)),
-        ];
-    }
-    let (token, l, res) = &JSON5_FLOAT_LOOKUP[w];
-    let txt = &txt[lex.off..];
-    if txt.len() >= *l && token == &txt[..*l] {
-        lex.col += l;
-        lex.off += l;
-        Ok(res.clone())
+    let mut n = text.len();
+    for (i, b) in text.iter().enumerate() {
+        match ISNUMBER[*b as usize] {
+            0 => {
+                n = i;
+                break;
+            }
+            2 if !is_float => is_float = true,
+            3 => is_hex = true,
+            _ => (),
+        }
+    }
+    // ISNUMBER only admits ASCII characters.
+    let t = unsafe { std::str::from_utf8_unchecked(&text[..n]) };
+    dofn(t, n, is_float, is_hex)
+}
+
+fn parse_json5_float<'a, V: Node<'a>>(text: &'a [u8], lex: &mut Lex) -> Result<V> {
+    let txt = &text[lex.off..];
+    let (sign, rest) = match txt[0] {
+        b'-' => (-1.0, &txt[1..]),
+        b'+' => (1.0, &txt[1..]),
+        _ => (1.0, txt),
+    };
+    let n = txt.len() - rest.len();
+    // lexeme is ASCII.
+    let lexeme = |m| unsafe { std::str::from_utf8_unchecked(&txt[..m]) };
+    if rest.starts_with(b"Infinity") {
+        lex.incr_col(n + 8);
+        Ok(V::non_finite(lexeme(n + 8), sign * f64::INFINITY))
+    } else if rest.starts_with(b"NaN") {
+        lex.incr_col(n + 3);
+        Ok(V::non_finite(lexeme(n + 3), f64::NAN))
     } else {
-        err_at!(ParseFail, msg: "{}", lex.format("expected json5 float"))
+        let at = expected(text, lex, "Infinity or NaN");
+        err_at!(ParseFail, at: at, msg: "expected json5 float")
     }
 }
 
-fn parse_string(text: &str, lex: &mut Lex) -> Result<Json> {
-    use self::Json::String as S;
+// Parse string value, strings without escapes are borrowed from the text.
+fn parse_string<'a>(text: &'a [u8], lex: &mut Lex, p: &Parser) -> Result<Cow<'a, str>> {
+    let json5 = p.profile != Profile::Strict;
+    let bs = &text[lex.off..];
 
-    let mut escape = false;
-    let mut res = String::new();
-    let mut chars = text[lex.off..].char_indices();
+    let quote = bs[0]; // skip the opening quote
+    if quote != b'"' && !(json5 && quote == b'\'') {
+        err_at!(ParseFail, at: lex.to_location(), msg: "invalid string")?;
+    }
 
-    let (i, ch) = chars.next().unwrap(); // skip the opening quote
-    if ch != '"' {
-        err_at!(ParseFail, msg: "{}", lex.format("invalid string"))?;
+    let n = bs[1..].iter().position(|b| *b == quote || *b == b'\\' || *b < 0x20);
+    if let Some(n) = n.filter(|n| bs[n + 1] == quote) {
+        let res = decode_utf8(&bs[1..n + 1], lex, 1)?;
+        check_string(res.len(), lex, p)?;
+        lex.incr_col(n + 2);
+        return Ok(Cow::Borrowed(res));
     }
 
-    while let Some((i, ch)) = chars.next() {
-        if !escape {
-            if ch == '\\' {
-                escape = true;
-                continue;
+    let mut res = String::new();
+    let mut i = 1;
+    loop {
+        // copy unescaped text as is, validating its encoding. Since bytes
+        // of a multi-byte sequence are never ASCII, the run ends on a
+        // character boundary.
+        let start = i;
+        while i < bs.len() && bs[i] != quote && bs[i] != b'\\' && bs[i] >= 0x20 {
+            i += 1;
+        }
+        res.push_str(decode_utf8(&bs[start..i], lex, start)?);
+        check_string(res.len(), lex, p)?;
+
+        let ch = match bs.get(i) {
+            Some(ch) => *ch,
+            None => break,
+        };
+        match ch {
+            ch if ch == quote => {
+                lex.incr_col(i + 1);
+                return Ok(Cow::Owned(res));
             }
-            match ch {
-                '"' => {
-                    lex.incr_col(i + 1);
-                    return Ok(S(res));
-                }
-                _ => res.push(ch),
+            b'\\' => (),
+            _ if p.profile == Profile::Strict => {
+                lex.incr_col(i);
+                err_at!(ParseFail, at: lex.to_location(), msg: "unescaped control character")?
+            }
+            b'\n' | b'\r' if p.profile == Profile::Json5 => {
+                lex.incr_col(i);
+                err_at!(ParseFail, at: lex.to_location(), msg: "unescaped line terminator")?
+            }
+            ch => {
+                res.push(char::from(ch));
+                i += 1;
+                continue;
             }
-            continue;
         }
 
-        // previous char was escape
-        match ch {
-            '"' => res.push('"'),
-            '\\' => res.push('\\'),
-            '/' => res.push('/'),
-            'b' => res.push('\x08'),
-            'f' => res.push('\x0c'),
-            'n' => res.push('\n'),
-            'r' => res.push('\r'),
-            't' => res.push('\t'),
-            'u' => match decode_json_hex_code(&mut chars, lex)? {
+        // escape sequence
+        let j = i;
+        i += 2;
+        match bs.get(j + 1).copied() {
+            Some(b'"') => res.push('"'),
+            Some(b'\\') => res.push('\\'),
+            Some(b'/') => res.push('/'),
+            Some(b'b') => res.push('\x08'),
+            Some(b'f') => res.push('\x0c'),
+            Some(b'n') => res.push('\n'),
+            Some(b'r') => res.push('\r'),
+            Some(b't') => res.push('\t'),
+            Some(b'u') => match decode_hex_code(bs, &mut i, lex, 4)? {
                 code1 @ 0xDC00..=0xDFFF => {
-                    lex.incr_col(i);
-                    err_at!(
-                        ParseFail,
-                        msg: "{}", lex.format(&format!("invalid codepoint {:x}", code1))
-                    )?;
+                    lex.incr_col(j);
+                    let msg = format!("invalid codepoint {:x}", code1);
+                    err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)?;
                 }
                 // Non-BMP characters are encoded as a sequence of
                 // two hex escapes, representing UTF-16 surrogates.
                 code1 @ 0xD800..=0xDBFF => {
-                    let code2 = decode_json_hex_code2(&mut chars, lex)?;
-                    if !(0xDC00..=0xDFFF).contains(&code2) {
+                    if !bs[i..].starts_with(b"\\u") {
                         lex.incr_col(i);
-                        err_at!(
-                            ParseFail,
-                            msg: "{}",
-                            lex.format(
-                                &format!("invalid codepoint surrogate {:x}", code2)
-                            )
-                        )?;
+                        err_at!(ParseFail, at: lex.to_location(), msg: "invalid string escape type")?
+                    }
+                    i += 2;
+                    let code2 = decode_hex_code(bs, &mut i, lex, 4)?;
+                    if !(0xDC00..=0xDFFF).contains(&code2) {
+                        lex.incr_col(j);
+                        let msg = format!("invalid codepoint surrogate {:x}", code2);
+                        err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)?;
                     }
                     let code = ((code1 - 0xD800) << 10) | ((code2 - 0xDC00) + 0x1_0000);
                     res.push(char::from_u32(code).unwrap());
@@ -181,177 +679,806 @@ fn parse_string(text: &str, lex: &mut Lex) -> Result<Json> {
                 n => match char::from_u32(n) {
                     Some(ch) => res.push(ch),
                     None => {
-                        lex.incr_col(i);
-                        err_at!(
-                            ParseFail,
-                            msg: "{}",
-                            lex.format(&format!("invalid unicode escape u{:x}", n))
-                        )?;
+                        lex.incr_col(j);
+                        let msg = format!("invalid unicode escape u{:x}", n);
+                        err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)?;
                     }
                 },
             },
+            // JSON5 escapes, refer https://spec.json5.org/#escapes
+            Some(b'\'') if json5 => res.push('\''),
+            Some(b'v') if json5 => res.push('\x0b'),
+            Some(b'0') if json5 && !bs.get(i).is_some_and(|b| b.is_ascii_digit()) => {
+                res.push('\x00')
+            }
+            Some(b'x') if json5 => {
+                let code = decode_hex_code(bs, &mut i, lex, 2)?;
+                res.push(char::from_u32(code).unwrap());
+            }
+            // line continuation
+            Some(b'\n') if json5 => (),
+            Some(b'\r') if json5 => {
+                if bs.get(i) == Some(&b'\n') {
+                    i += 1;
+                }
+            }
+            Some(ch) if json5 && !ch.is_ascii_digit() => {
+                let ch = decode_char(bs, j + 1, lex)?.unwrap();
+                i = j + 1 + ch.len_utf8();
+                // line continuation
+                if !matches!(ch, '\u{2028}' | '\u{2029}') {
+                    res.push(ch)
+                }
+            }
+            None => break,
             _ => {
-                lex.incr_col(i);
-                err_at!(ParseFail, msg: "{}", lex.format("invalid string escape type"))?
+                lex.incr_col(j + 1);
+                err_at!(ParseFail, at: lex.to_location(), msg: "invalid string escape type")?
             }
         }
-        escape = false;
     }
-    lex.incr_col(i);
-    err_at!(ParseFail, msg: "{}", lex.format("incomplete string"))
+    err_at!(ParseFail, at: lex.to_location(), msg: "incomplete string")
 }
 
-fn decode_json_hex_code2(chars: &mut CharIndices, lex: &mut Lex) -> Result<u32> {
-    if let Some((_, ch1)) = chars.next() {
-        if let Some((_, ch2)) = chars.next() {
-            if ch1 == '\\' && ch2 == 'u' {
-                return decode_json_hex_code(chars, lex);
+// Decode ``width`` hex digits starting from bs[*i], and advance *i past them.
+fn decode_hex_code(bs: &[u8], i: &mut usize, lex: &mut Lex, width: usize) -> Result<u32> {
+    let mut code = 0_u32;
+    for _ in 0..width {
+        match bs.get(*i).map(|b| char::from(*b).to_digit(16)) {
+            Some(Some(digit)) => code = code * 16 + digit,
+            Some(None) => {
+                let msg = format!("invalid string escape code {:?}", char::from(bs[*i]));
+                err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)?;
+            }
+            None => {
+                let msg = format!("incomplete string escape code {:x}", code);
+                err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)?;
             }
         }
+        *i += 1;
     }
-    err_at!(ParseFail, msg: "{}", lex.format("invalid string escape type"))
+    Ok(code)
 }
 
-fn decode_json_hex_code(chars: &mut CharIndices, lex: &mut Lex) -> Result<u32> {
-    let mut n = 0;
-    let mut code = 0_u32;
-    for (_, ch) in chars {
-        if (ch as u32) > 128 || HEXNUM[ch as usize] == 20 {
-            let msg = format!("invalid string escape code {:?}", ch);
-            err_at!(ParseFail, msg: "{}", lex.format(&msg))?;
+// Validate UTF-8 encoding of bs, located at lex.off + off in the text.
+#[inline]
+fn decode_utf8<'a>(bs: &'a [u8], lex: &Lex, off: usize) -> Result<&'a str> {
+    match std::str::from_utf8(bs) {
+        Ok(s) => Ok(s),
+        Err(err) => {
+            let n = off + err.valid_up_to();
+            let at = Location::new(lex.off + n, lex.row, lex.col + n);
+            err_at!(ParseFail, at: at, msg: "invalid utf-8 encoding")
+        }
+    }
+}
+
+// Decode the character at bs[i], where bs is the text from lex.off, returns
+// None at end of text.
+#[inline]
+fn decode_char(bs: &[u8], i: usize, lex: &Lex) -> Result<Option<char>> {
+    match bs.get(i) {
+        None => Ok(None),
+        Some(b) if b.is_ascii() => Ok(Some(char::from(*b))),
+        Some(b) => {
+            let n = match b {
+                0xC0..=0xDF => 2,
+                0xE0..=0xEF => 3,
+                _ => 4,
+            };
+            let s = decode_utf8(&bs[i..bs.len().min(i + n)], lex, i)?;
+            Ok(s.chars().next())
         }
-        code = code * 16 + u32::from(HEXNUM[ch as usize]);
+    }
+}
+
+// Values extracted from JSON text, for each pointer, refer jptr::extract.
+struct Extract {
+    frags: Vec<Vec<String>>,
+    results: Vec<Result<Json>>,
+}
+
+impl Parser {
+    // Parse only the values at JSON ``pointers``, refer jptr::extract.
+    pub(crate) fn extract(&self, text: &str, pointers: &[&str]) -> Vec<Result<Json>> {
+        let mut x = Extract { frags: vec![], results: vec![] };
+        let mut cands = vec![];
+        for (i, pointer) in pointers.iter().enumerate() {
+            match jptr::fragments_of(pointer) {
+                Ok(frags) => {
+                    cands.push(i);
+                    x.frags.push(frags);
+                    x.results.push(Ok(Json::Null));
+                }
+                Err(err) => {
+                    x.frags.push(vec![]);
+                    x.results.push(Err(err));
+                }
+            }
+        }
+
+        let (text, mut lex) = (text.as_bytes(), Lex::new(0, 1, 1));
+        let res = if text.len() > self.max_bytes {
+            let msg = format!("text exceeds max_bytes {}", self.max_bytes);
+            err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)
+        } else {
+            extract_value(text, &mut lex, self, &mut x, 0, &cands)
+        };
+        if let Err(err) = res {
+            cands.iter().for_each(|i| x.results[*i] = Err(err.clone()));
+        }
+        x.results
+    }
+}
+
+// Extract values at pointers, indexed by ``cands``, that match the path of
+// the value at lex.off up to ``depth``. Values not on the path of any
+// pointer, with empty ``cands``, are validated and skipped.
+fn extract_value(
+    text: &[u8],
+    lex: &mut Lex,
+    p: &Parser,
+    x: &mut Extract,
+    depth: usize,
+    cands: &[usize],
+) -> Result<()> {
+    parse_whitespace(text, lex, p)?;
+    not_eof(text, lex)?;
+
+    let b = text[lex.off];
+    // parse the value when a pointer ends here, and for array indices
+    // that are relative to the array's end.
+    let full = cands.iter().any(|i| match x.frags[*i].get(depth) {
+        None => true,
+        Some(frag) => b == b'[' && frag.parse::<usize>().is_err(),
+    });
+    match b {
+        b'[' if !full => extract_array(text, lex, p, x, depth, cands),
+        b'{' if !full => extract_object(text, lex, p, x, depth, cands),
+        // skipped scalar, validate without allocating.
+        _ if cands.is_empty() => parse_value::<JsonRef>(text, lex, p).map(|_| ()),
+        _ => {
+            let value: Json = parse_value(text, lex, p)?;
+            for i in cands.iter() {
+                x.results[*i] = lookup(&value, &x.frags[*i][depth..]);
+            }
+            Ok(())
+        }
+    }
+}
+
+fn lookup(mut value: &Json, frags: &[String]) -> Result<Json> {
+    for frag in frags.iter() {
+        value = value[frag.as_str()].to_result()?;
+    }
+    Ok(value.clone())
+}
+
+fn extract_array(
+    text: &[u8],
+    lex: &mut Lex,
+    p: &Parser,
+    x: &mut Extract,
+    depth: usize,
+    cands: &[usize],
+) -> Result<()> {
+    enter_container(lex, p)?;
+    lex.incr_col(1); // skip '['
+
+    // array indices are validated by extract_value.
+    let indices: Vec<(usize, usize)> = cands
+        .iter()
+        .map(|i| (*i, x.frags[*i][depth].parse::<usize>().unwrap()))
+        .collect();
+
+    parse_whitespace(text, lex, p)?;
+    if next_byte(text, lex)? == b',' {
+        err_at!(ParseFail, at: lex.to_location(), msg: "expected ','")?;
+    }
+    let mut n = 0;
+    while next_byte(text, lex)? != b']' {
+        let sub: Vec<usize> =
+            indices.iter().filter(|(_, index)| *index == n).map(|(i, _)| *i).collect();
+        extract_value(text, lex, p, x, depth + 1, &sub)?;
         n += 1;
-        if n == 4 {
-            break;
+        check_members(n, lex, p)?;
+        parse_item_separator(text, lex, p)?;
+    }
+    lex.incr_col(1); // skip ']'
+    lex.depth -= 1;
+
+    for (i, index) in indices.into_iter().filter(|(_, index)| *index >= n) {
+        x.results[i] = err_at!(IndexOutofBound, msg: "{}", index);
+    }
+    Ok(())
+}
+
+fn extract_object(
+    text: &[u8],
+    lex: &mut Lex,
+    p: &Parser,
+    x: &mut Extract,
+    depth: usize,
+    cands: &[usize],
+) -> Result<()> {
+    enter_container(lex, p)?;
+    lex.incr_col(1); // skip '{'
+
+    let mut found = vec![false; cands.len()];
+    parse_whitespace(text, lex, p)?;
+    if next_byte(text, lex)? == b'}' {
+        lex.incr_col(1);
+    } else {
+        let mut n = 0;
+        loop {
+            parse_whitespace(text, lex, p)?;
+            let key = match parse_property_key(text, lex, p)? {
+                Some(key) => key,
+                None => break,
+            };
+            parse_whitespace(text, lex, p)?;
+            check_next_byte(text, lex, b':')?;
+
+            // for repeated keys, the last occurrence wins.
+            let mut sub = vec![];
+            for (j, i) in cands.iter().enumerate() {
+                if x.frags[*i][depth] == key {
+                    found[j] = true;
+                    sub.push(*i);
+                }
+            }
+            extract_value(text, lex, p, x, depth + 1, &sub)?;
+
+            n += 1;
+            check_members(n, lex, p)?;
+            if parse_property_separator(text, lex, p)? {
+                break;
+            }
         }
     }
-    if n != 4 {
-        let msg = format!("incomplete string escape code {:x}", code);
-        err_at!(ParseFail, msg: "{}", lex.format(&msg))?;
+    lex.depth -= 1;
+
+    for (i, _) in cands.iter().zip(found).filter(|(_, found)| !found) {
+        x.results[*i] = err_at!(PropertyNotFound, msg: "{}", x.frags[*i][depth]);
     }
-    Ok(code)
+    Ok(())
 }
 
-fn parse_array(text: &str, lex: &mut Lex) -> Result<Json> {
+#[inline]
+fn is_lazy(lex: &Lex, p: &Parser) -> bool {
+    lex.depth >= p.lazy_depth || lex.pointers.contains(&lex.path)
+}
+
+// Capture array or object text without parsing it, refer Parser::lazy_depth.
+fn parse_lazy<'a, V: Node<'a>>(text: &'a [u8], lex: &mut Lex, p: &Parser) -> Result<V> {
+    let bs = bracketed(text, lex, p)?;
+    let captured = decode_utf8(bs, lex, 0)?;
+    match V::lazy(captured, lex.to_location(), p, lex.depth) {
+        Some(value) => {
+            lex.skip_bytes(bs);
+            Ok(value)
+        }
+        None if bs[0] == b'[' => parse_array(text, lex, p),
+        None => parse_object(text, lex, p),
+    }
+}
+
+// Return text of the array or object at lex.off, by matching brackets.
+fn bracketed<'a>(text: &'a [u8], lex: &Lex, p: &Parser) -> Result<&'a [u8]> {
+    let bs = &text[lex.off..];
+    match match_brackets(bs, p) {
+        Some(n) => Ok(&bs[..n]),
+        None => {
+            let msg = format!("unterminated {}", char::from(bs[0]));
+            err_at!(ParseFail, at: lex.to_location(), msg: "{}", msg)
+        }
+    }
+}
+
+// Return the length of array or object at the start of bs, by matching
+// brackets, skipping strings, and comments if profile allows JSON5.
+fn match_brackets(bs: &[u8], p: &Parser) -> Option<usize> {
+    let json5 = p.profile != Profile::Strict;
+    let (mut nesting, mut i) = (0_usize, 0);
+    while i < bs.len() {
+        match bs[i] {
+            b'[' | b'{' => nesting += 1,
+            b']' | b'}' => {
+                nesting -= 1;
+                if nesting == 0 {
+                    return Some(i + 1);
+                }
+            }
+            quote @ (b'"' | b'\'') if quote == b'"' || json5 => {
+                i += 1;
+                while i < bs.len() && bs[i] != quote {
+                    i += if bs[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if json5 && bs.get(i + 1) == Some(&b'/') => {
+                i += bs[i..].iter().position(|b| *b == b'\n').unwrap_or(bs.len() - i);
+            }
+            b'/' if json5 && bs.get(i + 1) == Some(&b'*') => {
+                i += bs[i + 2..].windows(2).position(|w| w == b"*/")? + 3;
+            }
+            _ => (),
+        }
+        i += 1;
+    }
+    None
+}
+
+// Parse array item or property value, tracking its path when capturing
+// values at JSON pointers, refer Parser::lazy_pointer.
+fn parse_member<'a, V, F>(text: &'a [u8], lex: &mut Lex, p: &Parser, frag: F) -> Result<V>
+where
+    V: Node<'a>,
+    F: FnOnce() -> String,
+{
+    if lex.pointers.is_empty() {
+        return parse_value(text, lex, p);
+    }
+    lex.path.push(frag());
+    let res = parse_value(text, lex, p);
+    lex.path.pop();
+    res
+}
+
+fn parse_array<'a, V: Node<'a>>(text: &'a [u8], lex: &mut Lex, p: &Parser) -> Result<V> {
+    enter_container(lex, p)?;
     lex.incr_col(1); // skip '['
 
-    let mut array: Vec<Json> = Vec::new();
-    parse_whitespace(text, lex);
-    if text[lex.off..].as_bytes()[0] == b',' {
-        err_at!(ParseFail, msg: "{}", lex.format("expected ','"))?;
+    let mut array: Vec<V> = Vec::new();
+    let depth = lex.depth;
+    loop {
+        match parse_array_items(text, lex, p, &mut array) {
+            Ok(()) => break,
+            Err(err) => match recover(text, lex, depth, err)? {
+                Some(b',') => lex.incr_col(1),
+                Some(b']') => {
+                    lex.incr_col(1);
+                    break;
+                }
+                _ => break,
+            },
+        }
+    }
+    lex.depth -= 1;
+    Ok(V::array(array))
+}
+
+// Parse array items till, and including, the closing ']'.
+fn parse_array_items<'a, V: Node<'a>>(
+    text: &'a [u8],
+    lex: &mut Lex,
+    p: &Parser,
+    array: &mut Vec<V>,
+) -> Result<()> {
+    parse_whitespace(text, lex, p)?;
+    if next_byte(text, lex)? == b',' {
+        err_at!(ParseFail, at: lex.to_location(), msg: "expected ','")?;
     }
     loop {
-        if text[lex.off..].as_bytes()[0] == b']' {
+        if next_byte(text, lex)? == b']' {
             // end of array.
             lex.incr_col(1);
-            break Ok(Json::Array(array));
+            break Ok(());
         }
 
-        array.push(parse_value(text, lex)?);
+        let index = array.len();
+        array.push(parse_member(text, lex, p, || index.to_string())?);
+        check_members(array.len(), lex, p)?;
+        parse_item_separator(text, lex, p)?;
+    }
+}
 
-        parse_whitespace(text, lex);
-        if text[lex.off..].as_bytes()[0] == b',' {
+// Kept out of parse_array_items, to limit the stack used per nesting level.
+pub(crate) fn parse_item_separator(text: &[u8], lex: &mut Lex, p: &Parser) -> Result<()> {
+    parse_whitespace(text, lex, p)?;
+    match next_byte(text, lex)? {
+        b',' => {
             // skip comma
             lex.incr_col(1);
-            parse_whitespace(text, lex);
+            parse_whitespace(text, lex, p)?;
+            if p.profile == Profile::Strict && next_byte(text, lex)? == b']' {
+                err_at!(ParseFail, at: lex.to_location(), msg: "trailing comma")?;
+            }
+        }
+        b']' => (),
+        _ if p.profile == Profile::Lenient => (),
+        _ => {
+            let at = expected(text, lex, "',' or ']'");
+            err_at!(ParseFail, at: at, msg: "expected ',' or ']'")?
         }
     }
+    Ok(())
 }
 
-fn parse_object(text: &str, lex: &mut Lex) -> Result<Json> {
+fn parse_object<'a, V: Node<'a>>(text: &'a [u8], lex: &mut Lex, p: &Parser) -> Result<V> {
+    enter_container(lex, p)?;
     lex.incr_col(1); // skip '{'
 
-    parse_whitespace(text, lex);
+    parse_whitespace(text, lex, p)?;
 
-    let mut m: Vec<Property> = Vec::new();
+    let mut m: Vec<V::Property> = Vec::new();
+    let (mut n, mut collected) = (0, vec![]);
 
-    if text[lex.off..].as_bytes()[0] == b'}' {
+    if next_byte(text, lex)? == b'}' {
         lex.incr_col(1);
-        return Ok(Json::Object(m));
+        lex.depth -= 1;
+        return Ok(V::object(m));
     }
 
+    let depth = lex.depth;
+    loop {
+        match parse_properties::<V>(text, lex, p, &mut m, &mut n, &mut collected) {
+            Ok(()) => break,
+            Err(err) => match recover(text, lex, depth, err)? {
+                Some(b',') => lex.incr_col(1),
+                Some(b'}') => {
+                    lex.incr_col(1);
+                    break;
+                }
+                _ => break,
+            },
+        }
+    }
+    lex.depth -= 1;
+    Ok(V::object(m))
+}
+
+// Parse object properties till, and including, the closing '}'.
+fn parse_properties<'a, V: Node<'a>>(
+    text: &'a [u8],
+    lex: &mut Lex,
+    p: &Parser,
+    m: &mut Vec<V::Property>,
+    n: &mut usize,
+    collected: &mut Vec<Cow<'a, str>>,
+) -> Result<()> {
     loop {
         // key
-        parse_whitespace(text, lex);
-        let key: String = match text[lex.off..].chars().next() {
-            Some('}') => {
-                lex.incr_col(1);
-                break Ok(Json::Object(m));
-            }
-            Some('"') => parse_string(text, lex)?.as_str().unwrap().to_string(),
-            Some(ch) if ch.is_alphabetic() => parse_identifier(text, lex),
-            _ => err_at!(ParseFail, msg:"{}", lex.format("invalid property key"))?,
+        parse_whitespace(text, lex, p)?;
+        let at = lex.to_location();
+        let key = match parse_property_key(text, lex, p)? {
+            Some(key) => key,
+            None => break Ok(()),
         };
         // colon
-        parse_whitespace(text, lex);
+        parse_whitespace(text, lex, p)?;
         check_next_byte(text, lex, b':')?;
 
         // value
-        parse_whitespace(text, lex);
-        let value = parse_value(text, lex)?;
+        parse_whitespace(text, lex, p)?;
+        let value = parse_member::<V, _>(text, lex, p, || key.to_string())?;
 
-        property::upsert_object_key(&mut m, Property::new(key, value));
+        *n += 1;
+        check_members(*n, lex, p)?;
+        insert_property::<V>(m, key, value, at, collected, p)?;
         //println!("parse {} {} {:?}", key, i, m);
 
         // is exit
-        parse_whitespace(text, lex);
-        let mut chars = text[lex.off..].chars();
-        match chars.next() {
-            None => err_at!(ParseFail, msg: "{}", lex.format("unexpected eof"))?,
-            Some(',') => {
-                lex.incr_col(1);
+        if parse_property_separator(text, lex, p)? {
+            break Ok(());
+        }
+    }
+}
+
+// Return None if object is closed, instead of a property key. Kept out of
+// parse_properties, to limit the stack used per nesting level.
+pub(crate) fn parse_property_key<'a>(
+    text: &'a [u8],
+    lex: &mut Lex,
+    p: &Parser,
+) -> Result<Option<Cow<'a, str>>> {
+    let key = match decode_char(&text[lex.off..], 0, lex)? {
+        Some('}') if p.profile == Profile::Strict => {
+            err_at!(ParseFail, at: lex.to_location(), msg: "trailing comma")?
+        }
+        Some('}') => {
+            lex.incr_col(1);
+            return Ok(None);
+        }
+        Some('"') => parse_string(text, lex, p)?,
+        Some('\'') if p.profile != Profile::Strict => parse_string(text, lex, p)?,
+        Some(ch) if (is_id_start(ch) || ch == '\\') && p.profile != Profile::Strict => {
+            let key = parse_identifier(text, lex)?;
+            check_string(key.len(), lex, p)?;
+            key
+        }
+        _ => {
+            let at = expected(text, lex, "property key");
+            err_at!(ParseFail, at: at, msg: "invalid property key")?
+        }
+    };
+    Ok(Some(key))
+}
+
+// Return true if object is closed.
+pub(crate) fn parse_property_separator(
+    text: &[u8],
+    lex: &mut Lex,
+    p: &Parser,
+) -> Result<bool> {
+    parse_whitespace(text, lex, p)?;
+    match text.get(lex.off) {
+        None => err_at!(ParseFail, at: lex.to_location(), msg: "unexpected eof")?,
+        Some(b',') => {
+            lex.incr_col(1);
+        }
+        Some(b'}') if p.profile != Profile::Lenient => {
+            lex.incr_col(1);
+            return Ok(true);
+        }
+        _ if p.profile == Profile::Lenient => (),
+        _ => {
+            let at = expected(text, lex, "',' or '}'");
+            err_at!(ParseFail, at: at, msg: "expected ',' or '}}'")?
+        }
+    }
+    Ok(false)
+}
+
+// When diagnosing, record the syntax error and skip to the next ',', ']' or
+// '}' at the current nesting level, returning that byte, or None at end of
+// text. Otherwise, and for errors other than syntax errors, fail with err.
+fn recover(text: &[u8], lex: &mut Lex, depth: usize, err: Error) -> Result<Option<u8>> {
+    record(lex, err)?;
+    lex.depth = depth;
+
+    let (mut nesting, mut quote) = (0_usize, None);
+    let (bs, mut i) = (&text[lex.off..], 0);
+    while let Some(ch) = bs.get(i).copied() {
+        match (quote, ch) {
+            (Some(_), b'\\') => i += 1,
+            (Some(q), ch) if ch == q => quote = None,
+            (Some(_), _) => (),
+            (None, b'"' | b'\'') => quote = Some(ch),
+            (None, b'[' | b'{') => nesting += 1,
+            (None, b',' | b']' | b'}') if nesting == 0 => break,
+            (None, b']' | b'}') => nesting -= 1,
+            (None, _) => (),
+        }
+        i += 1;
+    }
+    lex.skip_bytes(&bs[..i.min(bs.len())]);
+    Ok(text.get(lex.off).copied())
+}
+
+// When diagnosing, record the syntax error, skipping errors at the same
+// location as the previous one. Otherwise fail with err.
+fn record(lex: &mut Lex, err: Error) -> Result<()> {
+    match (lex.errors.as_mut(), err) {
+        (Some(errors), err @ Error::ParseFail(_, _, _)) => {
+            let loc = errors.last().and_then(|e| e.location());
+            if loc.map(|l| l.offset()) != err.location().map(|l| l.offset()) {
+                errors.push(err);
             }
-            _ => (),
+            Ok(())
+        }
+        (_, err) => Err(err),
+    }
+}
+
+// Insert property into object, handling duplicate keys as configured. Keys
+// whose values are already collected into an array are tracked in
+// ``collected``, to distinguish them from array values.
+fn insert_property<'a, V: Node<'a>>(
+    m: &mut Vec<V::Property>,
+    key: Cow<'a, str>,
+    value: V,
+    at: Location,
+    collected: &mut Vec<Cow<'a, str>>,
+    p: &Parser,
+) -> Result<()> {
+    let off = match m.binary_search_by(|x| V::key(x).cmp(&key)) {
+        Ok(off) => off,
+        Err(off) => {
+            m.insert(off, V::property(key, value));
+            return Ok(());
         }
+    };
+    let prev = V::value_mut(&mut m[off]);
+    match p.duplicates {
+        Duplicates::LastWins => *prev = value,
+        Duplicates::FirstWins => (),
+        Duplicates::Error => {
+            let msg = format!("duplicate property key {:?}", key);
+            err_at!(ParseFail, at: at, msg: "{}", msg)?
+        }
+        Duplicates::Collect if collected.contains(&key) => {
+            prev.as_array_mut().unwrap().push(value)
+        }
+        Duplicates::Collect => {
+            let first = std::mem::replace(prev, V::null());
+            *prev = V::array(vec![first, value]);
+            collected.push(key);
+        }
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn enter_container(lex: &mut Lex, p: &Parser) -> Result<()> {
+    lex.depth += 1;
+    if lex.depth > p.max_depth {
+        let msg = format!("nesting exceeds max_depth {}", p.max_depth);
+        err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
     }
+    Ok(())
 }
 
 #[inline]
-fn parse_identifier(text: &str, lex: &mut Lex) -> String {
-    let (off, mut n) = (lex.off, 0);
-    for (i, ch) in text[lex.off..].char_indices() {
-        if ch.is_alphanumeric() {
-            continue;
+fn check_string(n: usize, lex: &mut Lex, p: &Parser) -> Result<()> {
+    if n > p.max_string {
+        let msg = format!("string exceeds max_string {}", p.max_string);
+        err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
+    }
+    Ok(())
+}
+
+#[inline]
+pub(crate) fn check_members(n: usize, lex: &mut Lex, p: &Parser) -> Result<()> {
+    if n > p.max_members {
+        let msg = format!("container exceeds max_members {}", p.max_members);
+        err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
+    }
+    Ok(())
+}
+
+// Parse property key that is an ECMAScript IdentifierName, refer
+// https://spec.json5.org/#prod-JSON5Identifier
+#[inline]
+fn parse_identifier<'a>(text: &'a [u8], lex: &mut Lex) -> Result<Cow<'a, str>> {
+    // key is decoded only when the identifier has escapes.
+    let mut key: Option<String> = None;
+    let (bs, mut i) = (&text[lex.off..], 0);
+    loop {
+        let (ch, n) = match decode_char(bs, i, lex)? {
+            Some('\\') if bs.get(i + 1) == Some(&b'u') => {
+                let mut j = i + 2;
+                match char::from_u32(decode_hex_code(bs, &mut j, lex, 4)?) {
+                    Some(ch) if i == 0 && is_id_start(ch) => (ch, j - i),
+                    Some(ch) if i > 0 && is_id_part(ch) => (ch, j - i),
+                    _ => {
+                        err_at!(ParseFail, at: lex.to_location(), msg: "invalid identifier")?
+                    }
+                }
+            }
+            Some('\\') => {
+                err_at!(ParseFail, at: lex.to_location(), msg: "invalid identifier")?
+            }
+            Some(ch) if i == 0 && is_id_start(ch) => (ch, ch.len_utf8()),
+            Some(ch) if i > 0 && is_id_part(ch) => (ch, ch.len_utf8()),
+            _ => break,
+        };
+        if n != ch.len_utf8() || key.is_some() {
+            // validated by decode_char.
+            let prefix = unsafe { std::str::from_utf8_unchecked(&bs[..i]) };
+            key.get_or_insert_with(|| prefix.to_string()).push(ch);
         }
-        n = i;
-        break;
+        i += n;
+    }
+    lex.incr_col(i);
+    match key {
+        Some(key) => Ok(Cow::Owned(key)),
+        // validated by decode_char.
+        None => Ok(Cow::Borrowed(unsafe { std::str::from_utf8_unchecked(&bs[..i]) })),
     }
-    lex.off += n;
-    text[off..off + n].to_string()
 }
 
+// IdentifierStart, as per ECMAScript, without unicode escapes.
 #[inline]
-fn parse_whitespace(text: &str, lex: &mut Lex) {
-    for (i, ch) in text[lex.off..].char_indices() {
-        //println!("{} {}", u32::from(ch), ch.is_whitespace());
-        if ch.is_whitespace() {
-            if (u32::from(ch)) < 256 && ch == '\n' {
-                lex.row += 1;
-                lex.col = 0;
-            } else {
-                lex.col += 1;
+pub(crate) fn is_id_start(ch: char) -> bool {
+    match ch {
+        'a'..='z' | 'A'..='Z' | '$' | '_' => true,
+        ch if ch.is_ascii() => false,
+        ch => ch.is_id_start(),
+    }
+}
+
+// IdentifierPart, as per ECMAScript, without unicode escapes.
+#[inline]
+pub(crate) fn is_id_part(ch: char) -> bool {
+    match ch {
+        'a'..='z' | 'A'..='Z' | '0'..='9' | '$' | '_' => true,
+        ch if ch.is_ascii() => false,
+        ch => ch.is_id_continue() || matches!(ch, '\u{200c}' | '\u{200d}'),
+    }
+}
+
+// Skip whitespace, and comments if profile allows JSON5.
+#[inline]
+pub(crate) fn parse_whitespace(text: &[u8], lex: &mut Lex, p: &Parser) -> Result<()> {
+    loop {
+        while let Some(b) = text.get(lex.off) {
+            //println!("{} {}", b, char::from(*b));
+            let ok = match p.profile {
+                Profile::Strict => matches!(b, b' ' | b'\t' | b'\n' | b'\r'),
+                _ if b.is_ascii() => is_json5_whitespace(char::from(*b)),
+                Profile::Json5 => match decode_char(&text[lex.off..], 0, lex)? {
+                    Some(ch) if is_json5_whitespace(ch) => {
+                        lex.incr_row_col(ch);
+                        continue;
+                    }
+                    _ => false,
+                },
+                Profile::Lenient => match decode_char(&text[lex.off..], 0, lex)? {
+                    Some(ch) if ch.is_whitespace() || is_json5_whitespace(ch) => {
+                        lex.incr_row_col(ch);
+                        continue;
+                    }
+                    _ => false,
+                },
+            };
+            if !ok {
+                break;
             }
-            continue;
+            lex.incr_row_col(char::from(*b));
+        }
+
+        let txt = &text[lex.off..];
+        let comment = if p.profile == Profile::Strict {
+            None
+        } else if txt.starts_with(b"//") {
+            // single line comment, line terminator is skipped as whitespace,
+            // \u{2028} and \u{2029} are encoded as E2 80 A8 and E2 80 A9.
+            let lt = |bs: &[u8]| {
+                matches!(bs, [b'\n' | b'\r', ..] | [0xE2, 0x80, 0xA8 | 0xA9, ..])
+            };
+            Some((0..txt.len()).find(|i| lt(&txt[*i..])).unwrap_or(txt.len()))
+        } else if txt.starts_with(b"/*") {
+            match txt[2..].windows(2).position(|w| w == b"*/") {
+                Some(n) => Some(n + 4),
+                None => {
+                    err_at!(ParseFail, at: lex.to_location(), msg: "unterminated comment")?
+                }
+            }
+        } else {
+            None
+        };
+        match comment {
+            Some(n) => lex.skip_bytes(&txt[..n]),
+            None => break Ok(()),
         }
-        lex.off += i;
-        break;
     }
 }
 
+// JSON5 whitespace, refer https://spec.json5.org/#white-space
+#[inline]
+fn is_json5_whitespace(ch: char) -> bool {
+    matches!(
+        ch,
+        '\t' | '\n'
+            | '\x0b'
+            | '\x0c'
+            | '\r'
+            | ' '
+            | '\u{a0}'
+            | '\u{feff}'
+            | '\u{1680}'
+            | '\u{2000}'
+            ..='\u{200a}'
+                | '\u{2028}'
+                | '\u{2029}'
+                | '\u{202f}'
+                | '\u{205f}'
+                | '\u{3000}'
+    )
+}
+
 #[inline]
-fn check_next_byte(text: &str, lex: &mut Lex, b: u8) -> Result<()> {
-    let progbytes = text[lex.off..].as_bytes();
+pub(crate) fn check_next_byte(text: &[u8], lex: &mut Lex, b: u8) -> Result<()> {
+    let progbytes = &text[lex.off..];
 
     if progbytes.is_empty() {
-        err_at!(ParseFail, msg: "{}", lex.format(&format!("missing token {}", b)))?;
+        let at = expected(text, lex, &char::from(b).to_string());
+        err_at!(ParseFail, at: at, msg: "missing token {}", b)?;
     }
 
     if progbytes[0] != b {
-        err_at!(
-            ParseFail,
-            msg: "{}", lex.format(&format!("invalid byte {}, {}", b, progbytes[0]))
-        )?;
+        let at = expected(text, lex, &char::from(b).to_string());
+        err_at!(ParseFail, at: at, msg: "invalid byte {}, {}", b, progbytes[0])?;
     }
 
     lex.incr_col(1);
@@ -360,28 +1487,39 @@ fn check_next_byte(text: &str, lex: &mut Lex, b: u8) -> Result<()> {
 }
 
 #[inline]
-fn not_eof(text: &str, lex: &mut Lex) -> Result<()> {
+pub(crate) fn next_byte(text: &[u8], lex: &mut Lex) -> Result<u8> {
+    match text.get(lex.off) {
+        Some(b) => Ok(*b),
+        None => err_at!(ParseFail, at: lex.to_location(), msg: "unexpected eof"),
+    }
+}
+
+#[inline]
+fn not_eof(text: &[u8], lex: &mut Lex) -> Result<()> {
     if text[lex.off..].is_empty() {
-        err_at!(ParseFail, msg: "{}", lex.format("unexpected eof"))
+        err_at!(ParseFail, at: lex.to_location(), msg: "unexpected eof")
     } else {
         Ok(())
     }
 }
 
-static HEXNUM: [u8; 256] = [
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 20, 10, 11,
-    12, 13, 14, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 20, 10, 11, 12, 13, 14, 15, 0, 0, 0, 0, 0, 0, 0, 0, 0, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-    20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20, 20,
-];
+// Location of lex, along with the expected token and the token found
+// in its place, which is empty at end of text.
+pub(crate) fn expected(text: &[u8], lex: &Lex, token: &str) -> Location {
+    let bs = &text[lex.off..];
+    let txt = match std::str::from_utf8(&bs[..bs.len().min(64)]) {
+        Ok(txt) => txt,
+        Err(err) => std::str::from_utf8(&bs[..err.valid_up_to()]).unwrap(),
+    };
+    let n = match txt.chars().next() {
+        Some(ch) if ch.is_alphanumeric() => {
+            txt.find(|ch: char| !is_id_part(ch)).unwrap_or(txt.len())
+        }
+        Some(ch) => ch.len_utf8(),
+        None => 0,
+    };
+    lex.to_location().expect(token, &txt[..n])
+}
 
 // These days, with unicode, white-spaces have become more complicated :/.
 static _WS_LOOKUP: [u8; 256] = [
@@ -401,7 +1539,7 @@ static ISNUMBER: [u8; 256] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 2, 0, 1, 1, 1, 1, 1, 1, 1, 1,
     1, 1, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0, 0, 0, 0, 0, 3, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
@@ -409,3 +1547,7 @@ static ISNUMBER: [u8; 256] = [
     0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
     0, 0, 0, 0,
 ];
+
+#[cfg(test)]
+#[path = "parse_test.rs"]
+mod parse_test;
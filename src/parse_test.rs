@@ -0,0 +1,438 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use crate::json::Json;
+use crate::parse::{Parser, Profile};
+
+#[test]
+fn test_profile_strict() {
+    let parser = Parser::new().profile(Profile::Strict);
+
+    let texts = [
+        "null",
+        " \t\r\n true \t\r\n ",
+        "[0, -0, 10, -1.5, 0.25e10, 1E-2, 2e+3]",
+        r#"{"a": [1, {"b": "é\n"}], "c": {}}"#,
+    ];
+    for text in texts.iter() {
+        let value = parser.parse(text).unwrap();
+        assert_eq!(value, text.parse::<Json>().unwrap(), "{}", text);
+    }
+
+    let texts = [
+        "00",
+        "001",
+        "-01",
+        "+1",
+        ".5",
+        "1.",
+        "1e",
+        "0x10",
+        "Infinity",
+        "-Infinity",
+        "NaN",
+        "{a: 1}",
+        "[1,]",
+        r#"{"a": 1,}"#,
+        "[1 2]",
+        r#"{"a": 1 "b": 2}"#,
+        "\"a\tb\"",
+        "1 2",
+        "\u{a0}1",
+        "[1",
+    ];
+    for text in texts.iter() {
+        assert!(parser.parse(text).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn test_profile_json5() {
+    let parser = Parser::new().profile(Profile::Json5);
+
+    let value = parser.parse("[0x10, .5, 5., +1, Infinity, -Infinity, NaN,]").unwrap();
+    assert_eq!(value.to_array().unwrap().len(), 7);
+    assert_eq!(value.get("/0").unwrap(), Json::new(16));
+    assert_eq!(value.get("/1").unwrap(), Json::new(0.5));
+    assert_eq!(value.get("/2").unwrap(), Json::new(5.0));
+    assert_eq!(value.get("/3").unwrap(), Json::new(1));
+
+    let value = parser.parse("{a: 1, \"b\": \"x\ty\",}").unwrap();
+    assert_eq!(value.get("/a").unwrap(), Json::new(1));
+    assert_eq!(value.get("/b").unwrap(), Json::new("x\ty"));
+
+    let texts = ["00", "0x", "1e", "[1 2]", "1 2", "[1,,]", "{a: 1 b: 2}", "\"a\nb\""];
+    for text in texts.iter() {
+        assert!(parser.parse(text).is_err(), "{}", text);
+    }
+}
+
+#[test]
+fn test_profile_lenient() {
+    let parser = Parser::new();
+
+    assert_eq!(parser.parse("00").unwrap(), Json::new(0));
+    assert_eq!(parser.parse("[1 2]").unwrap(), Json::new(vec![1, 2]));
+    assert_eq!(parser.parse("1 2").unwrap(), Json::new(1));
+    assert_eq!(parser.parse("{a: 1 b: 2}").unwrap(), "{a: 1, b: 2}".parse().unwrap());
+
+    assert!(parser.parse("[1").is_err());
+    assert!(parser.parse("{\"a\": 1").is_err());
+}
+
+#[test]
+fn test_json5_grammar() {
+    let parser = Parser::new().profile(Profile::Json5);
+
+    let text =
+        "// comment\n{/* a */ $_a: 'x\\'y', \\u0062c: \"p\\\nq\", d: [1, /**/ 2,],}";
+    let value = parser.parse(text).unwrap();
+    assert_eq!(value.get("/$_a").unwrap(), Json::new("x'y"));
+    assert_eq!(value.get("/bc").unwrap(), Json::new("pq"));
+    assert_eq!(value.get("/d").unwrap(), Json::new(vec![1, 2]));
+
+    let value = parser.parse(r#"'\x41\v\0\a'"#).unwrap();
+    assert_eq!(value, Json::new("A\x0b\x00a"));
+    assert_eq!(parser.parse("+Infinity").unwrap(), Json::new(f64::INFINITY));
+
+    // identifiers are matched with unicode ID_Start and ID_Continue.
+    let value = parser.parse("{a\u{301}: 1, \u{e9}\u{345}: 2, \u{2118}: 3}").unwrap();
+    assert_eq!(value.get("/a\u{301}").unwrap(), Json::new(1));
+    assert_eq!(value.get("/\u{e9}\u{345}").unwrap(), Json::new(2));
+    assert_eq!(value.get("/\u{2118}").unwrap(), Json::new(3));
+
+    let texts = [
+        "/* x",
+        "// x",
+        "[1] /* x",
+        "{a-b: 1}",
+        "{1a: 1}",
+        r#"'\01'"#,
+        r#"'\xg0'"#,
+        "{\u{345}: 1}",
+        "{\\u0345: 1}",
+    ];
+    for text in texts.iter() {
+        assert!(parser.parse(text).is_err(), "{}", text);
+    }
+}
+
+// Refer testdata/json5-tests.md, file extension indicates the
+// expected outcome:
+// * `.json`, valid JSON, hence valid JSON5.
+// * `.json5`, valid JSON5 but not valid JSON.
+// * `.js` and `.txt`, invalid JSON5.
+#[test]
+fn test_json5_tests() {
+    let strict = Parser::new().profile(Profile::Strict);
+    let json5 = Parser::new().profile(Profile::Json5);
+
+    let dir =
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/json5-tests");
+    let (mut n, mut todo) = (0, 0);
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let dir = entry.unwrap().path();
+        let hidden =
+            dir.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.'));
+        if !dir.is_dir() || hidden {
+            continue; // README, LICENSE and such.
+        }
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            let text = std::fs::read_to_string(&path).unwrap();
+            let name = path.display();
+            match path.extension().and_then(|ext| ext.to_str()) {
+                // pending upstream, but supported by this parser.
+                Some("json5") if dir.ends_with("todo") => {
+                    assert!(json5.parse(&text).is_ok(), "{}", name);
+                    todo += 1;
+                }
+                Some(_) if dir.ends_with("todo") => panic!("unexpected {}", name),
+                Some("json") => {
+                    let value = strict.parse(&text).unwrap();
+                    assert_eq!(json5.parse(&text).unwrap(), value, "{}", name);
+                }
+                Some("json5") => {
+                    assert!(strict.parse(&text).is_err(), "{}", name);
+                    assert!(json5.parse(&text).is_ok(), "{}", name);
+                }
+                Some("js") | Some("txt") => {
+                    assert!(json5.parse(&text).is_err(), "{}", name);
+                }
+                _ => panic!("unexpected {}", name),
+            }
+            n += 1;
+        }
+    }
+    assert!(n > 0);
+    assert_eq!(todo, 2);
+}
+
+#[test]
+fn test_parse_limits() {
+    use crate::Error;
+
+    let is_limit =
+        |res: crate::Result<Json>| matches!(res, Err(Error::LimitExceeded(_, _, _)));
+
+    let text = "[".repeat(1_000_000);
+    assert!(is_limit(Parser::new().parse(&text)));
+    assert!(is_limit(text.parse::<Json>()));
+    let text = "{a:".repeat(1_000_000);
+    assert!(is_limit(Parser::new().parse(&text)));
+    let text =
+        "[".repeat(crate::DEFAULT_MAX_DEPTH) + &"]".repeat(crate::DEFAULT_MAX_DEPTH);
+    assert!(Parser::new().parse(&text).is_ok());
+
+    let parser = Parser::new().max_depth(2);
+    assert!(parser.parse("[{}, [1]]").is_ok());
+    assert!(parser.parse(r#"{"a": [1], "b": {}}"#).is_ok());
+    assert!(is_limit(parser.parse("[[[]]]")));
+    assert!(is_limit(parser.parse("{a: {b: {}}}")));
+
+    let parser = Parser::new().max_bytes(8);
+    assert!(parser.parse("[1,2,3]").is_ok());
+    assert!(is_limit(parser.parse("[1, 2, 3]")));
+
+    let parser = Parser::new().max_string(3);
+    assert!(parser.parse(r#"{"abc": "Abc"}"#).is_ok());
+    assert!(is_limit(parser.parse(r#""abcd""#)));
+    assert!(is_limit(parser.parse(r#"{"abcd": 1}"#)));
+    assert!(is_limit(parser.parse("{abcd: 1}")));
+
+    let parser = Parser::new().max_members(2).profile(Profile::Strict);
+    assert!(is_limit(parser.parse(r#"[1, [2, 3], {"a": 1, "b": 2}]"#)));
+    assert!(parser.parse(r#"[[2, 3], {"a": 1, "b": 2}]"#).is_ok());
+    assert!(is_limit(parser.parse(r#"{"a": 1, "b": 2, "c": 3}"#)));
+
+    let parser = Parser::new().max_digits(4);
+    assert!(parser.parse("[1234, -1.5, 0xff]").is_ok());
+    assert!(is_limit(parser.parse("12345")));
+    assert!(is_limit(parser.parse("-1.25")));
+}
+
+#[test]
+fn test_duplicates() {
+    use crate::parse::Duplicates;
+
+    let text = r#"{"a": 1, "b": [true], "a": 2, "b": null, "a": [3]}"#;
+
+    let value = Parser::new().parse(text).unwrap();
+    assert_eq!(value, r#"{"a": [3], "b": null}"#.parse().unwrap());
+
+    let value = Parser::new().duplicates(Duplicates::FirstWins).parse(text).unwrap();
+    assert_eq!(value, r#"{"a": 1, "b": [true]}"#.parse().unwrap());
+
+    let value = Parser::new().duplicates(Duplicates::Collect).parse(text).unwrap();
+    let refv = r#"{"a": [1, 2, [3]], "b": [[true], null]}"#;
+    assert_eq!(value, refv.parse().unwrap());
+
+    let value = Parser::new().duplicates(Duplicates::Collect).parse("{a: [1]}").unwrap();
+    assert_eq!(value, "{a: [1]}".parse().unwrap());
+
+    let parser = Parser::new().duplicates(Duplicates::Error);
+    assert!(parser.parse(r#"{"a": 1, "b": {"a": 2}}"#).is_ok());
+    let err = parser.parse("{\"a\": 1,\n \"b\": 2, \"a\": 3}").unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        msg.contains("duplicate property key \"a\" at offset:18 line:2 col:10"),
+        "{}",
+        msg
+    );
+}
+
+#[test]
+fn test_error_location() {
+    let parser = Parser::new().profile(Profile::Strict);
+
+    let err = parser.parse("{\"a\": [1, 2}").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.offset(), loc.line(), loc.column()), (11, 1, 12));
+    assert_eq!(loc.expected(), Some("',' or ']'"));
+    assert_eq!(loc.found(), Some("}"));
+    assert!(err.to_string().ends_with("expected ',' or ']' at offset:11 line:1 col:12"));
+
+    let err = parser.parse("{\"a\"\n 1}").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.offset(), loc.line(), loc.column()), (6, 2, 2));
+    assert_eq!((loc.expected(), loc.found()), (Some(":"), Some("1")));
+
+    // columns are counted in bytes.
+    let err = parser.parse("[\"\u{e9}\", x]").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.offset(), loc.line(), loc.column()), (7, 1, 8));
+
+    let err = parser.parse("[true, fals]").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.expected(), loc.found()), (Some("false"), Some("fals")));
+
+    let err = parser.parse("[1] x").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.expected(), loc.found()), (Some("end of text"), Some("x")));
+
+    let err = parser.parse("[1,").unwrap_err();
+    let loc = err.location().unwrap();
+    assert_eq!((loc.offset(), loc.expected(), loc.found()), (3, None, None));
+
+    assert!((Json::new(1) + Json::new("a")).to_error().unwrap().location().is_none());
+}
+
+#[test]
+fn test_diagnose() {
+    let offsets = |errors: &[crate::Error]| -> Vec<usize> {
+        errors.iter().map(|e| e.location().unwrap().offset()).collect()
+    };
+
+    let parser = Parser::new().profile(Profile::Json5);
+
+    let (value, errors) = parser.diagnose("{a: [1, 2], b: 'x'}");
+    assert_eq!(value, parser.parse("{a: [1, 2], b: 'x'}").unwrap());
+    assert!(errors.is_empty());
+
+    let text = "{\n  a: [1, tru, {c: 'x,]}'}, 4 5, 6],\n  b: -,\n  'd': null,\n}";
+    let (value, errors) = parser.diagnose(text);
+    let refv = "{a: [1, {c: \"x,]}\"}, 4, 6], d: null}";
+    assert_eq!(value, refv.parse().unwrap());
+    assert_eq!(offsets(&errors), vec![11, 31, 43], "{:?}", errors);
+    assert_eq!(errors[2].location().unwrap().line(), 3);
+
+    let (value, errors) = parser.diagnose("[1, {a: 2], 3}");
+    assert_eq!(value, "[1, {a: 2}]".parse().unwrap());
+    assert_eq!(offsets(&errors), vec![9, 10], "{:?}", errors);
+
+    let (value, errors) = parser.diagnose("[1, [2, x");
+    assert_eq!(value, "[1, [2]]".parse().unwrap());
+    assert_eq!(offsets(&errors), vec![8, 9], "{:?}", errors);
+
+    let (value, errors) = parser.diagnose("x");
+    assert_eq!(value, Json::Null);
+    assert_eq!(offsets(&errors), vec![0]);
+
+    let (value, errors) = parser.max_depth(2).diagnose("[1, [x, [3]]]");
+    assert_eq!(value, Json::Null);
+    assert!(matches!(errors.last(), Some(crate::Error::LimitExceeded(_, _, _))));
+
+    let err = Parser::new().profile(Profile::Json5).parse("[1, x, y]").unwrap_err();
+    assert_eq!(err.location().unwrap().offset(), 4);
+}
+
+#[test]
+fn test_parse_slice() {
+    use crate::Error;
+
+    let jsons = include!("../testdata/test_simple.jsons");
+    for text in jsons.iter() {
+        let value = Json::from_slice(text.as_bytes()).unwrap();
+        assert_eq!(value, text.parse::<Json>().unwrap(), "{}", text);
+        assert_eq!(Json::from_reader(text.as_bytes()).unwrap(), value, "{}", text);
+    }
+
+    let parser = Parser::new().profile(Profile::Json5);
+    let text =
+        "// \u{e9}\n{\u{e9}t\u{e9}: '\u{1f600}\\\u{2028}x',\u{3000}b: \"\\\u{e9}\"}";
+    let value = parser.parse_slice(text.as_bytes()).unwrap();
+    assert_eq!(value, parser.parse(text).unwrap());
+    assert_eq!(value.get("/\u{e9}t\u{e9}").unwrap(), Json::new("\u{1f600}x"));
+
+    // invalid encoding is not validated within comments.
+    assert!(parser.parse_slice(b"/* \xff */ [1]").is_ok());
+
+    let texts: [(&[u8], usize); 6] = [
+        (b"[\"ab\xff\"]", 4),
+        (b"[\"ab\xc3\"]", 4),
+        (b"{'a': '\xe2\x82'}", 7),
+        (b"{a\xff: 1}", 2),
+        (b"[1,\xe3\x80]", 3),
+        (b"[\"\\\xff\"]", 3),
+    ];
+    for (text, off) in texts.iter() {
+        let err = parser.parse_slice(text).unwrap_err();
+        assert_eq!(err.location().unwrap().offset(), *off, "{:?} {}", text, err);
+    }
+
+    let parser = Parser::new().max_bytes(8);
+    assert!(parser.parse_reader(b"[1,2,3]".as_ref()).is_ok());
+    let res = parser.parse_reader(b"[1, 2, 3, 4]".as_ref());
+    assert!(matches!(res, Err(Error::LimitExceeded(_, _, _))));
+}
+
+#[test]
+fn test_lazy() {
+    use crate::Error;
+
+    let text = r#"{"id": 1, "a": {"b": [1, {"c": "]}"}], "d": {}}, "e": [[2], 3]}"#;
+    let eager = Parser::new().parse(text).unwrap();
+
+    for depth in 0..4 {
+        let parser = Parser::new().lazy_depth(depth);
+        let value = parser.parse(text).unwrap();
+        assert_eq!(value, eager, "{}", depth);
+        assert_eq!(value.cmp(&eager), std::cmp::Ordering::Equal, "{}", depth);
+        assert_eq!(eager.cmp(&value), std::cmp::Ordering::Equal, "{}", depth);
+        assert_eq!(value.to_string(), eager.to_string(), "{}", depth);
+        assert_eq!(value.get("/a/b/1/c").unwrap(), Json::new("]}"), "{}", depth);
+        assert_eq!(value["e"][0][0], Json::new(2), "{}", depth);
+        assert_eq!(value.clone() + Json::Null, eager, "{}", depth);
+    }
+
+    let value = Parser::new().lazy_depth(1).parse(text).unwrap();
+    let props = value.to_object().unwrap();
+    let lazy: Vec<bool> = props.iter().map(|p| p.as_value().is_lazy()).collect();
+    assert_eq!(lazy, vec![true, true, false]);
+
+    let parser = Parser::new().lazy_pointer("/a/b").lazy_pointer("/e/0");
+    let mut value = parser.parse(text).unwrap();
+    assert!(!value["a"].is_lazy());
+    assert!(value.to_object().unwrap()[0].as_value()["b"].is_lazy());
+    assert_eq!(value, eager);
+    value.set("/a/b/0", Json::new(10)).unwrap();
+    value.append("/e/0", Json::new(20)).unwrap();
+    value.delete("/a/d").unwrap();
+    let res: Json =
+        r#"{"id": 1, "a": {"b": [10, {"c": "]}"}]}, "e": [[2, 20], 3]}"#.parse().unwrap();
+    assert_eq!(value, res);
+
+    let parser = Parser::new().lazy_pointer("").profile(Profile::Json5);
+    let text = "[1, /* ] */ 'a]', // ]\n {b: \"\\\"]\"}]";
+    let mut value = parser.parse(text).unwrap();
+    assert!(value.is_lazy());
+    value.compute().unwrap();
+    assert!(!value.is_lazy());
+    assert_eq!(value, Parser::new().profile(Profile::Json5).parse(text).unwrap());
+
+    // syntax errors are reported on access, located within the document.
+    let text = "{\"a\": 1,\n \"b\": [1, 2,\n 3 4]}";
+    let parser = Parser::new().profile(Profile::Json5).lazy_depth(1);
+    let mut value = parser.parse(text).unwrap();
+    assert_eq!(value.get("/a").unwrap(), Json::new(1));
+    let err = value.get("/b/0").unwrap_err();
+    let loc = err.location().unwrap().clone();
+    assert_eq!((loc.offset(), loc.line()), (25, 3));
+    let eager = Parser::new().profile(Profile::Json5).parse(text).unwrap_err();
+    assert_eq!(eager.location(), Some(&loc));
+    assert_eq!(value.validate().unwrap_err(), err);
+    let lazy = parser.parse(text).unwrap()["b"].clone();
+    assert!(lazy.is_lazy());
+    let results = [
+        -lazy.clone(),
+        !lazy.clone(),
+        lazy.clone() + Json::new(1),
+        lazy.clone() << Json::new(1),
+        lazy.clone() >> Json::new(1),
+        Json::new(1) & lazy.clone(),
+        lazy.clone() | Json::new(1),
+        lazy.clone() ^ Json::new(1),
+    ];
+    for res in results.into_iter() {
+        assert_eq!(res.to_result().unwrap_err(), err);
+    }
+
+    let parser = Parser::new().lazy_depth(1).max_depth(3);
+    let value = parser.parse("[[[1]], [[[2]]]]").unwrap();
+    assert_eq!(value[0][0][0], Json::new(1));
+    assert!(matches!(value[1].to_result(), Err(Error::LimitExceeded(_, _, _))));
+
+    let res = Parser::new().lazy_depth(1).parse("[1, [2, \"]\"]");
+    assert!(matches!(res, Err(Error::ParseFail(_, _, _))));
+    let res = Parser::new().lazy_pointer("a").parse("[1]");
+    assert!(matches!(res, Err(Error::JptrFail(_, _))));
+}
@@ -63,10 +63,3 @@ impl Property {
         self.1 = value;
     }
 }
-
-pub fn upsert_object_key(obj: &mut Vec<Property>, prop: Property) {
-    match obj.binary_search_by(|p| p.as_key().cmp(prop.as_key())) {
-        Ok(off) => obj[off] = prop,
-        Err(off) => obj.insert(off, prop),
-    }
-}
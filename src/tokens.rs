@@ -0,0 +1,248 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::borrow::Cow;
+
+use crate::parse::{self, Parser};
+use crate::{error::Location, json_ref::JsonRef, lex::Lex, Error, Result};
+
+/// Token enumerates parsing events, generated by [Tokens] iterator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token<'a> {
+    StartObject,
+    /// Property key, borrowed from the text unless it has escape sequences.
+    Key(Cow<'a, str>),
+    EndObject,
+    StartArray,
+    EndArray,
+    /// Null, boolean, number or string value.
+    Scalar(JsonRef<'a>),
+}
+
+/// Tokens is a pull parser, iterating over JSON text as a sequence of
+/// [Token] events, each along with the [Location] of its first byte.
+/// Created using [Parser::tokens].
+///
+/// Tokens use the same lexing routines as [Parser::parse], hence the
+/// parser's profile and limits apply. But values are not collected into
+/// a document, so nesting is not bounded by the stack, and memory used
+/// is independent of the size of the text. To process documents bigger
+/// than RAM, iterate over a memory-mapped file. As properties are not
+/// collected, [Parser::duplicates] does not apply.
+///
+/// Iteration stops after the first error. With lenient profile, text
+/// after the value is ignored.
+///
+/// ```
+/// use jsondata::{JsonRef, Parser, Token};
+///
+/// let text = r#"{"a": [1, null]}"#;
+/// let tokens: Vec<Token> = Parser::new()
+///     .tokens(text)
+///     .map(|item| item.map(|(token, _)| token))
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+/// assert_eq!(tokens, vec![
+///     Token::StartObject,
+///     Token::Key("a".into()),
+///     Token::StartArray,
+///     Token::Scalar(JsonRef::Integer("1")),
+///     Token::Scalar(JsonRef::Null),
+///     Token::EndArray,
+///     Token::EndObject,
+/// ]);
+/// ```
+pub struct Tokens<'a> {
+    text: &'a [u8],
+    lex: Lex,
+    parser: Parser,
+    // open containers, as (is_object, number of members).
+    stack: Vec<(bool, usize)>,
+    state: State,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum State {
+    Start,
+    Value,
+    ArrayStart,
+    ObjectStart,
+    Key,
+    Separator,
+    End,
+    Done,
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(text: &'a [u8], parser: Parser) -> Tokens<'a> {
+        Tokens {
+            text,
+            lex: Lex::new(0, 1, 1),
+            parser,
+            stack: vec![],
+            state: State::Start,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<(Token<'a>, Location)>> {
+        let (text, p) = (self.text, &self.parser);
+        let lex = &mut self.lex;
+        loop {
+            match self.state {
+                State::Start => {
+                    if text.len() > p.max_bytes {
+                        let msg = format!("text exceeds max_bytes {}", p.max_bytes);
+                        err_at!(LimitExceeded, at: lex.to_location(), msg: "{}", msg)?;
+                    }
+                    self.state = State::Value;
+                }
+                State::Value => {
+                    parse::parse_whitespace(text, lex, p)?;
+                    let at = lex.to_location();
+                    let token = match parse::next_byte(text, lex)? {
+                        b'[' => {
+                            parse::enter_container(lex, p)?;
+                            lex.incr_col(1);
+                            self.stack.push((false, 0));
+                            self.state = State::ArrayStart;
+                            Token::StartArray
+                        }
+                        b'{' => {
+                            parse::enter_container(lex, p)?;
+                            lex.incr_col(1);
+                            self.stack.push((true, 0));
+                            self.state = State::ObjectStart;
+                            Token::StartObject
+                        }
+                        _ => {
+                            let value = parse::parse_value::<JsonRef>(text, lex, p)?;
+                            self.state = after_value(&self.stack);
+                            Token::Scalar(value)
+                        }
+                    };
+                    return Ok(Some((token, at)));
+                }
+                State::ArrayStart => {
+                    parse::parse_whitespace(text, lex, p)?;
+                    if parse::next_byte(text, lex)? == b',' {
+                        let at = parse::expected(text, lex, "value");
+                        err_at!(ParseFail, at: at, msg: "expected value")?;
+                    }
+                    self.state = State::Separator;
+                }
+                State::ObjectStart => {
+                    parse::parse_whitespace(text, lex, p)?;
+                    if parse::next_byte(text, lex)? == b'}' {
+                        return Ok(Some(close(lex, &mut self.stack, &mut self.state)));
+                    }
+                    self.state = State::Key;
+                }
+                State::Key => {
+                    parse::parse_whitespace(text, lex, p)?;
+                    let at = lex.to_location();
+                    let key = match parse::parse_property_key(text, lex, p)? {
+                        Some(key) => key,
+                        None => {
+                            self.stack.pop();
+                            lex.depth -= 1;
+                            self.state = after_value(&self.stack);
+                            return Ok(Some((Token::EndObject, at)));
+                        }
+                    };
+                    parse::parse_whitespace(text, lex, p)?;
+                    parse::check_next_byte(text, lex, b':')?;
+                    member(lex, p, &mut self.stack)?;
+                    self.state = State::Value;
+                    return Ok(Some((Token::Key(key), at)));
+                }
+                State::Separator => match self.stack.last() {
+                    Some((false, n)) => {
+                        if *n > 0 {
+                            parse::parse_item_separator(text, lex, p)?;
+                        }
+                        if parse::next_byte(text, lex)? == b']' {
+                            return Ok(Some(close(
+                                lex,
+                                &mut self.stack,
+                                &mut self.state,
+                            )));
+                        }
+                        member(lex, p, &mut self.stack)?;
+                        self.state = State::Value;
+                    }
+                    _ => {
+                        parse::parse_whitespace(text, lex, p)?;
+                        let at = lex.to_location();
+                        if parse::parse_property_separator(text, lex, p)? {
+                            self.stack.pop();
+                            lex.depth -= 1;
+                            self.state = after_value(&self.stack);
+                            return Ok(Some((Token::EndObject, at)));
+                        }
+                        self.state = State::Key;
+                    }
+                },
+                State::End => {
+                    self.state = State::Done;
+                    p.parse_trailing(text, lex)?;
+                }
+                State::Done => return Ok(None),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokens<'a> {
+    type Item = Result<(Token<'a>, Location)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.next_token() {
+            Ok(item) => item.map(Ok),
+            Err(err) => {
+                self.state = State::Done;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+impl<'a> std::iter::FusedIterator for Tokens<'a> {}
+
+// State after an array item or property value, or after the document
+// value when no container is open.
+fn after_value(stack: &[(bool, usize)]) -> State {
+    if stack.is_empty() {
+        State::End
+    } else {
+        State::Separator
+    }
+}
+
+// Count a new member of the innermost container.
+fn member(lex: &mut Lex, p: &Parser, stack: &mut [(bool, usize)]) -> Result<()> {
+    if let Some((_, n)) = stack.last_mut() {
+        *n += 1;
+        parse::check_members(*n, lex, p)?;
+    }
+    Ok(())
+}
+
+// Close the innermost container at its closing bracket.
+fn close<'a>(
+    lex: &mut Lex,
+    stack: &mut Vec<(bool, usize)>,
+    state: &mut State,
+) -> (Token<'a>, Location) {
+    let at = lex.to_location();
+    lex.incr_col(1);
+    lex.depth -= 1;
+    let token = match stack.pop() {
+        Some((true, _)) => Token::EndObject,
+        _ => Token::EndArray,
+    };
+    *state = after_value(stack);
+    (token, at)
+}
+
+#[cfg(test)]
+#[path = "tokens_test.rs"]
+mod tokens_test;
@@ -0,0 +1,120 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use super::*;
+use crate::{parse::Profile, Error};
+
+fn collect<'a>(parser: &Parser, text: &'a str) -> Result<Vec<(Token<'a>, usize)>> {
+    parser
+        .tokens(text)
+        .map(|item| item.map(|(token, at)| (token, at.offset())))
+        .collect()
+}
+
+#[test]
+fn test_tokens() {
+    use Token::*;
+
+    let text = r#" {"a": [1, -2.5, "x\ty"], "b\tc": {}, "d": [[], null, true]} "#;
+    let tokens = collect(&Parser::new(), text).unwrap();
+    let refs = vec![
+        (StartObject, 1),
+        (Key("a".into()), 2),
+        (StartArray, 7),
+        (Scalar(JsonRef::Integer("1")), 8),
+        (Scalar(JsonRef::Float("-2.5")), 11),
+        (Scalar(JsonRef::String("x\ty".into())), 17),
+        (EndArray, 23),
+        (Key("b\tc".into()), 26),
+        (StartObject, 34),
+        (EndObject, 35),
+        (Key("d".into()), 38),
+        (StartArray, 43),
+        (StartArray, 44),
+        (EndArray, 45),
+        (Scalar(JsonRef::Null), 48),
+        (Scalar(JsonRef::Bool(true)), 54),
+        (EndArray, 58),
+        (EndObject, 59),
+    ];
+    assert_eq!(tokens, refs);
+    assert!(matches!(tokens[1].0, Key(Cow::Borrowed("a"))));
+    assert!(matches!(tokens[7].0, Key(Cow::Owned(_))));
+
+    let tokens = collect(&Parser::new(), "10").unwrap();
+    assert_eq!(tokens, vec![(Scalar(JsonRef::Integer("10")), 0)]);
+
+    let text = "{a: [1 2], b: 'c',}";
+    let tokens = collect(&Parser::new(), text).unwrap();
+    assert_eq!(tokens.len(), 9);
+    assert_eq!(tokens[8], (EndObject, 18));
+
+    let parser = Parser::new().profile(Profile::Json5);
+    let text = "[1, {/* x */ k: 0x10,},]";
+    let tokens = collect(&parser, text).unwrap();
+    assert_eq!(tokens[3], (Key("k".into()), 13));
+    assert_eq!(tokens[4], (Scalar(JsonRef::Integer("0x10")), 16));
+    assert_eq!(tokens[6], (EndArray, 23));
+
+    let mut tokens = Parser::new().tokens("[\n  true]");
+    tokens.next();
+    let (_, at) = tokens.next().unwrap().unwrap();
+    assert_eq!((at.offset(), at.line()), (4, 2));
+}
+
+#[test]
+fn test_tokens_errors() {
+    let parser = Parser::new().profile(Profile::Strict);
+    for (text, offset) in [
+        ("[1, 2] x", 7),
+        ("[1 2]", 3),
+        ("[1, 2,]", 6),
+        ("{\"a\" 1}", 5),
+        ("{\"a\": 1,}", 8),
+        ("[,1]", 1),
+        ("[1, 2", 5),
+        ("", 0),
+    ] {
+        let items: Vec<_> = parser.tokens(text).collect();
+        let err = items.last().unwrap().as_ref().unwrap_err();
+        assert!(matches!(err, Error::ParseFail(_, _, _)), "{}", text);
+        assert_eq!(err.location().unwrap().offset(), offset, "{}", text);
+        assert_eq!(items.iter().filter(|item| item.is_err()).count(), 1);
+    }
+
+    let err = parser.tokens("[,").last().unwrap().unwrap_err();
+    let at = err.location().unwrap();
+    assert_eq!((at.offset(), at.expected(), at.found()), (1, Some("value"), Some(",")));
+
+    let mut tokens = parser.tokens("[nul]");
+    assert!(tokens.next().unwrap().is_ok());
+    assert!(tokens.next().unwrap().is_err());
+    assert!(tokens.next().is_none());
+    assert!(tokens.next().is_none());
+
+    let res = collect(&Parser::new(), "[1, 2] x");
+    assert_eq!(res.unwrap().len(), 4);
+}
+
+#[test]
+fn test_tokens_limits() {
+    let parser = Parser::new().max_depth(2);
+    let err = collect(&parser, "[[[1]]]").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+    assert!(collect(&parser, "[[1], [2]]").is_ok());
+
+    let parser = Parser::new().max_members(2);
+    assert!(collect(&parser, r#"[1, {"a": 1, "b": 2}]"#).is_ok());
+    let err = collect(&parser, r#"{"a": 1, "b": 2, "c": 3}"#).unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+    assert!(collect(&parser, "[1, 2, 3]").is_err());
+
+    let err = collect(&Parser::new().max_bytes(4), "[1, 2]").unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded(_, _, _)));
+
+    // nesting is not recursive.
+    let depth = 100_000;
+    let text = "[".repeat(depth) + &"]".repeat(depth);
+    let parser = Parser::new().max_depth(depth);
+    let tokens: Vec<_> = parser.tokens(&text).collect::<Result<_>>().unwrap();
+    assert_eq!(tokens.len(), depth * 2);
+}
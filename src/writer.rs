@@ -0,0 +1,171 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::{fmt, io, io::Write};
+
+use crate::{format::Format, json::Json, jsons::Framing, Error, Result};
+
+/// Writer can serialize a stream of [Json] values into any [Write]
+/// instance, without materializing the JSON text in memory.
+/// For Example:
+///
+/// ```
+/// use jsondata::{Json, Writer};
+///
+/// let mut writer: Writer<Vec<u8>> = Vec::new().into();
+/// writer.write(&Json::new(10)).unwrap();
+/// writer.write(&Json::new(vec![Json::new(true), Json::Null])).unwrap();
+///
+/// let out = writer.into_inner().unwrap();
+/// assert_eq!(out, b"10\n[true,null]\n");
+/// ```
+///
+/// Output is buffered, and each value is terminated by a newline, so
+/// that the text can be read back using [crate::Jsons] iterator. By
+/// default values are serialized in compact form, same as the [Display]
+/// implementation for [Json], use [Writer::with_format] to pretty print
+/// them instead.
+///
+/// [Write]: std::io::Write
+/// [Display]: std::fmt::Display
+pub struct Writer<W>
+where
+    W: io::Write,
+{
+    inner: io::BufWriter<W>,
+    format: Option<Format>,
+    framing: Framing,
+}
+
+impl<W> From<W> for Writer<W>
+where
+    W: io::Write,
+{
+    fn from(output: W) -> Writer<W> {
+        Writer {
+            inner: io::BufWriter::new(output),
+            format: None,
+            framing: Framing::Concatenated,
+        }
+    }
+}
+
+impl<W> Writer<W>
+where
+    W: io::Write,
+{
+    /// Pretty print values using the supplied format.
+    pub fn with_format(mut self, format: Format) -> Writer<W> {
+        self.format = Some(format);
+        self
+    }
+
+    /// Delimit values as per ``framing``, so that the text can be read
+    /// back using [crate::Jsons::framing], default is
+    /// [Framing::Concatenated]. With [Framing::Ndjson], pretty printed
+    /// values are written in single line, and with [Framing::Rfc7464]
+    /// each value is preceded by the record separator `\x1E`.
+    ///
+    /// ```
+    /// use jsondata::{Format, Framing, Json, Writer};
+    ///
+    /// let mut writer = Writer::from(Vec::new()).framing(Framing::Rfc7464);
+    /// writer.write(&Json::new(vec![1, 2])).unwrap();
+    /// assert_eq!(writer.into_inner().unwrap(), b"\x1e[1,2]\n");
+    ///
+    /// let format = Format::new().colon_space(false);
+    /// let mut writer = Writer::from(Vec::new()).with_format(format).framing(Framing::Ndjson);
+    /// writer.write(&"{\"a\": [1, 2]}".parse().unwrap()).unwrap();
+    /// assert_eq!(writer.into_inner().unwrap(), b"{\"a\":[1,2]}\n");
+    /// ```
+    pub fn framing(mut self, framing: Framing) -> Writer<W> {
+        self.framing = framing;
+        self
+    }
+
+    /// Serialize ``json`` value followed by a newline.
+    pub fn write(&mut self, json: &Json) -> Result<()> {
+        if self.framing == Framing::Rfc7464 {
+            err_at!(IoError, self.inner.write_all(b"\x1e"))?;
+        }
+        match (&self.format, self.framing) {
+            (Some(format), Framing::Ndjson) => {
+                write_with(&mut self.inner, json, &format.clone().single_line())?
+            }
+            (Some(format), _) => write_with(&mut self.inner, json, format)?,
+            (None, _) => json.write_to(&mut self.inner)?,
+        }
+        err_at!(IoError, self.inner.write_all(b"\n"))
+    }
+
+    /// Flush buffered text to the underlying writer.
+    pub fn flush(&mut self) -> Result<()> {
+        err_at!(IoError, self.inner.flush())
+    }
+
+    /// Flush buffered text and return the underlying writer.
+    pub fn into_inner(self) -> Result<W> {
+        err_at!(IoError, self.inner.into_inner())
+    }
+}
+
+pub(crate) fn write_compact<W>(w: &mut W, json: &Json) -> Result<()>
+where
+    W: io::Write,
+{
+    use std::fmt::Write;
+
+    let mut adapter = Adapter { inner: w, err: None };
+    let res = err_at!(IoError, write!(adapter, "{}", json));
+    adapter.into_result(res)
+}
+
+pub(crate) fn write_with<W>(w: &mut W, json: &Json, format: &Format) -> Result<()>
+where
+    W: io::Write,
+{
+    let mut adapter = Adapter { inner: w, err: None };
+    let res = format.write(&mut adapter, json);
+    adapter.into_result(res)
+}
+
+// Adapter to serialize JSON text, using fmt::Write, directly into a
+// io::Write instance, remembering the io::Error that caused fmt::Error.
+struct Adapter<'a, W>
+where
+    W: io::Write,
+{
+    inner: &'a mut W,
+    err: Option<io::Error>,
+}
+
+impl<'a, W> fmt::Write for Adapter<'a, W>
+where
+    W: io::Write,
+{
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.inner.write_all(s.as_bytes()) {
+            Ok(_) => Ok(()),
+            Err(err) => {
+                self.err = Some(err);
+                Err(fmt::Error)
+            }
+        }
+    }
+}
+
+impl<'a, W> Adapter<'a, W>
+where
+    W: io::Write,
+{
+    fn into_result(self, res: Result<()>) -> Result<()> {
+        match (res, self.err) {
+            (Ok(_), _) => Ok(()),
+            (Err(_), Some(err)) => err_at!(IoError, msg: "{}", err),
+            (Err(err), None) => Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+#[path = "writer_test.rs"]
+mod writer_test;
@@ -0,0 +1,120 @@
+// Copyright © 2019 R Pratap Chakravarthy. All rights reserved.
+
+use std::io;
+
+use crate::{format::Format, json::Json, jsons::Jsons, writer::Writer, Error};
+
+struct Failing(usize);
+
+impl io::Write for Failing {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.0 < buf.len() {
+            Err(io::Error::other("disk full"))
+        } else {
+            self.0 -= buf.len();
+            Ok(buf.len())
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_write_to() {
+    let jsons = include!("../testdata/test_simple.jsons");
+
+    for (i, text) in jsons.iter().enumerate() {
+        let json: Json = text.parse().unwrap();
+        let mut out: Vec<u8> = vec![];
+        json.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), json.to_string(), "testcase {}", i);
+    }
+
+    // deferred numbers are written as they were parsed.
+    let json: Json = "[0x1F, -12, 1.50, 2e3]".parse().unwrap();
+    let mut out: Vec<u8> = vec![];
+    json.write_to(&mut out).unwrap();
+    assert_eq!(out, b"[0x1F,-12,1.50,2e3]");
+}
+
+#[test]
+fn test_write_to_fail() {
+    let json: Json = r#"{"key": "a long string value"}"#.parse().unwrap();
+    match json.write_to(&mut Failing(10)) {
+        Err(Error::IoError(_, msg)) => assert!(msg.contains("disk full"), "{}", msg),
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn test_writer() {
+    let values = vec![
+        Json::new(10),
+        Json::new("hello\nworld"),
+        r#"{"a": [1, 2.5, null], "b": {}}"#.parse().unwrap(),
+    ];
+
+    let mut writer: Writer<Vec<u8>> = Vec::new().into();
+    for value in values.iter() {
+        writer.write(value).unwrap();
+    }
+    let out = writer.into_inner().unwrap();
+    let refs = "10\n\"hello\\nworld\"\n{\"a\":[1,2.5,null],\"b\":{}}\n";
+    assert_eq!(String::from_utf8(out.clone()).unwrap(), refs);
+
+    let mut writer: Writer<Vec<u8>> = Vec::new().into();
+    writer = writer.with_format(Format::new());
+    for value in values.iter() {
+        writer.write(value).unwrap();
+    }
+    writer.flush().unwrap();
+    let out = writer.into_inner().unwrap();
+    let refs = values[2].to_pretty_string();
+    assert!(String::from_utf8(out.clone()).unwrap().ends_with(&(refs + "\n")));
+
+    let js: Jsons<&[u8]> = out.as_slice().into();
+    let items: Vec<Json> = js.map(|item| item.unwrap()).collect();
+    assert_eq!(items, values);
+}
+
+#[test]
+fn test_writer_fail() {
+    let mut writer: Writer<Failing> = Failing(4).into();
+    writer.write(&Json::new("a string value that does not fit")).unwrap();
+    match writer.flush() {
+        Err(Error::IoError(_, msg)) => assert!(msg.contains("disk full"), "{}", msg),
+        res => panic!("unexpected {:?}", res),
+    }
+}
+
+#[test]
+fn test_writer_framing() {
+    use crate::jsons::Framing;
+
+    let values: Vec<Json> = vec![
+        Json::new(10),
+        Json::new("a\nb"),
+        r#"{"a": [1, {"b": null}], "c": "d"}"#.parse().unwrap(),
+        Json::new(true),
+    ];
+    for framing in [Framing::Concatenated, Framing::Ndjson, Framing::Rfc7464] {
+        let mut writer = Writer::from(Vec::new()).with_format(Format::new());
+        writer = writer.framing(framing);
+        for value in values.iter() {
+            writer.write(value).unwrap();
+        }
+        let out = writer.into_inner().unwrap();
+        let text = String::from_utf8(out.clone()).unwrap();
+        match framing {
+            Framing::Ndjson => assert_eq!(text.lines().count(), values.len()),
+            Framing::Rfc7464 => assert_eq!(text.matches('\x1e').count(), values.len()),
+            Framing::Concatenated => (),
+        }
+
+        let js = Jsons::from(out.as_slice()).framing(framing);
+        let items: Vec<Json> = js.map(|item| item.unwrap()).collect();
+        assert_eq!(items, values, "{:?}", framing);
+    }
+}
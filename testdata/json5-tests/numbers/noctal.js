@@ -0,0 +1 @@
+080
\ No newline at end of file